@@ -0,0 +1,191 @@
+// Background music for every game and sound effects the games ask for with `PlaySfx`. Games only
+// send the events, the windowed apps add `audio_plugin` to actually play them, so headless tests
+// run silently.
+
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::minigame::MinigameRegistry;
+use crate::pause::PauseState;
+use crate::storage::{load_ron, save_ron};
+use crate::GameState;
+
+const AUDIO_FILE: &str = "audio.ron";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Sfx {
+    Bounce,
+    Shoot,
+    EnemyDeath,
+    PlayerHit,
+    Catch,
+    Hazard,
+    PowerUp,
+    Jump,
+    Crash,
+}
+
+impl Sfx {
+    const ALL: [Sfx; 9] = [
+        Sfx::Bounce,
+        Sfx::Shoot,
+        Sfx::EnemyDeath,
+        Sfx::PlayerHit,
+        Sfx::Catch,
+        Sfx::Hazard,
+        Sfx::PowerUp,
+        Sfx::Jump,
+        Sfx::Crash,
+    ];
+
+    fn path(self) -> &'static str {
+        match self {
+            Sfx::Bounce => "audio/bounce.wav",
+            Sfx::Shoot => "audio/shoot.wav",
+            Sfx::EnemyDeath => "audio/enemy_death.wav",
+            Sfx::PlayerHit => "audio/player_hit.wav",
+            Sfx::Catch => "audio/catch.wav",
+            Sfx::Hazard => "audio/hazard.wav",
+            Sfx::PowerUp => "audio/power_up.wav",
+            Sfx::Jump => "audio/jump.wav",
+            Sfx::Crash => "audio/crash.wav",
+        }
+    }
+}
+
+// Sent by the games whenever something should make a sound
+#[derive(Event, Clone, Copy, Debug)]
+pub struct PlaySfx(pub Sfx);
+
+// Volumes go from 0 to 1, music and effects are scaled by the master volume
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct AudioSettings {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+    pub muted: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        AudioSettings {
+            master: 1.0,
+            music: 0.6,
+            sfx: 0.8,
+            muted: false,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub fn load() -> Self {
+        load_ron(AUDIO_FILE).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        save_ron(AUDIO_FILE, self);
+    }
+
+    pub fn music_volume(&self) -> f32 {
+        if self.muted { 0.0 } else { self.master * self.music }
+    }
+
+    pub fn sfx_volume(&self) -> f32 {
+        if self.muted { 0.0 } else { self.master * self.sfx }
+    }
+}
+
+// Loaded once so effects don't have to wait for the disk the first time they play
+#[derive(Resource)]
+struct SfxHandles(Vec<(Sfx, Handle<AudioSource>)>);
+
+// The music that's playing, for the game with this id
+#[derive(Component)]
+struct MusicTrack(&'static str);
+
+pub fn audio_plugin(app: &mut App) {
+    app.insert_resource(AudioSettings::load())
+        .add_systems(Startup, load_sfx)
+        .add_systems(Update, (play_sfx, switch_music, set_music_volume.run_if(resource_changed::<AudioSettings>)))
+        .add_systems(OnEnter(PauseState::Paused), pause_music)
+        .add_systems(OnExit(PauseState::Paused), resume_music);
+}
+
+fn load_sfx(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let handles = Sfx::ALL.iter().map(|&sfx| (sfx, asset_server.load(sfx.path()))).collect();
+    commands.insert_resource(SfxHandles(handles));
+}
+
+fn play_sfx(
+    mut commands: Commands,
+    mut events: EventReader<PlaySfx>,
+    handles: Res<SfxHandles>,
+    settings: Res<AudioSettings>,
+) {
+    let volume = settings.sfx_volume();
+    for PlaySfx(sfx) in events.read() {
+        if volume <= 0.0 {
+            continue;
+        }
+        let Some((_, source)) = handles.0.iter().find(|(handle_sfx, _)| handle_sfx == sfx) else {
+            continue;
+        };
+        commands.spawn(AudioBundle {
+            source: source.clone(),
+            settings: PlaybackSettings::DESPAWN.with_volume(Volume::new(volume)),
+        });
+    }
+}
+
+// Starts the game's music when a game starts and stops it when the game is left. Restarting keeps
+// the music going.
+fn switch_music(
+    mut commands: Commands,
+    mut transitions: EventReader<StateTransitionEvent<GameState>>,
+    asset_server: Res<AssetServer>,
+    registry: Option<Res<MinigameRegistry>>,
+    settings: Res<AudioSettings>,
+    tracks: Query<(Entity, &MusicTrack)>,
+) {
+    for transition in transitions.read() {
+        let game = match transition.after {
+            GameState::Playing(id) | GameState::Restarting(id) => Some(id),
+            _ => None,
+        };
+        if tracks.iter().any(|(_, track)| Some(track.0) == game) {
+            continue;
+        }
+        for (entity, _) in &tracks {
+            commands.entity(entity).despawn();
+        }
+        let music = game.and_then(|id| registry.as_ref()?.by_id(id)?.music().map(|music| (id, music)));
+        if let Some((id, music)) = music {
+            commands.spawn((
+                AudioBundle {
+                    source: asset_server.load(music),
+                    settings: PlaybackSettings::LOOP.with_volume(Volume::new(settings.music_volume())),
+                },
+                MusicTrack(id),
+            ));
+        }
+    }
+}
+
+fn set_music_volume(settings: Res<AudioSettings>, sinks: Query<&AudioSink, With<MusicTrack>>) {
+    for sink in &sinks {
+        sink.set_volume(settings.music_volume());
+    }
+}
+
+fn pause_music(sinks: Query<&AudioSink, With<MusicTrack>>) {
+    for sink in &sinks {
+        sink.pause();
+    }
+}
+
+fn resume_music(sinks: Query<&AudioSink, With<MusicTrack>>) {
+    for sink in &sinks {
+        sink.play();
+    }
+}
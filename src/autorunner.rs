@@ -0,0 +1,249 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+
+use crate::audio::{PlaySfx, Sfx};
+use crate::controls::Action;
+use crate::game_over::{RunEnded, RunState};
+use crate::hud::{hud_text, Health, Score, ScoreText};
+use crate::player::Player;
+use crate::playfield::PLAYFIELD;
+use crate::minigame::Minigame;
+use crate::rng::GameRng;
+use crate::pause::PauseState;
+use crate::{despawn_screen, GameState, GameplaySet, OnGameScreen};
+
+#[derive(Bundle)]
+struct FloorBundle {
+    sprite_bundle:SpriteBundle,
+    tiling:ImageScaleMode,
+}
+
+
+#[derive(Component)]
+struct Rock;
+
+#[derive(Bundle)]
+struct ObstacleBundle {
+    rock:Rock,
+    sprite_bundle:SpriteBundle,
+    rigidbody:RigidBody,
+    collider:Collider,
+    velocity:Velocity,
+}
+
+// The floor runs along the bottom of the playfield, everything else stands on it
+const FLOOR_HEIGHT: f32 = 64.0;
+const FLOOR_TOP: f32 = -PLAYFIELD.height / 2.0 + FLOOR_HEIGHT;
+// Rocks roll in from just past the right edge and are gone once they're past the left one
+const ROCK_MARGIN: f32 = 100.0;
+
+#[derive(Resource)]
+struct RockTime {
+    timer:Timer,
+}
+
+// a point is scored for every second survived
+#[derive(Resource)]
+struct ScoreTimer {
+    timer:Timer,
+}
+
+impl FloorBundle {
+    fn new(m_texture:Handle<Image>) -> FloorBundle {
+        FloorBundle {
+            sprite_bundle: SpriteBundle {
+                texture: m_texture,
+                transform: Transform::from_xyz(0.,FLOOR_TOP - FLOOR_HEIGHT/2.,0.),
+                sprite: Sprite {
+                    custom_size:Some(Vec2::new(PLAYFIELD.width,FLOOR_HEIGHT)),
+                    ..default()
+                },
+                ..default()
+            },
+            tiling:ImageScaleMode::Tiled {
+                tile_x: true,
+                tile_y: true,
+                stretch_value: 1.,
+            },
+        }
+    }
+}
+
+impl ObstacleBundle {
+    fn new(m_texture:Handle<Image>, rng:&mut GameRng) -> ObstacleBundle {
+        ObstacleBundle {
+            rock: Rock,
+            sprite_bundle: SpriteBundle {
+                texture: m_texture,
+                transform: Transform::from_xyz(PLAYFIELD.right() + ROCK_MARGIN,FLOOR_TOP + 46.,0.)
+                    .with_scale(Vec3::new(0.25,0.25,1.)),
+                ..default()
+            },
+            rigidbody: RigidBody::KinematicVelocityBased,
+            velocity: Velocity {
+                linvel: Vec2::new(-rng.gen_range(200..230) as f32,0.),
+                ..default()
+            },
+            collider: Collider::ball(105.),
+        }
+    }
+}
+
+pub const STATE: GameState = GameState::Playing("autorunner");
+
+pub struct CrabRunner;
+
+impl Minigame for CrabRunner {
+    fn name(&self) -> &'static str {
+        "Crab Autorunner"
+    }
+
+    fn description(&self) -> &'static str {
+        "Jump over the rocks as long as you can"
+    }
+
+    fn icon(&self) -> &'static str {
+        "harmful1.png"
+    }
+
+    fn state(&self) -> GameState {
+        STATE
+    }
+
+    fn controls(&self) -> &'static [(Action, KeyCode)] {
+        &[(Action::Jump, KeyCode::Space)]
+    }
+
+    fn music(&self) -> Option<&'static str> {
+        Some("audio/autorunner_music.wav")
+    }
+
+    fn build(&self, app: &mut App) {
+        autorunner_plugin(app);
+    }
+}
+
+// GAME FOUR PLUGIN
+fn autorunner_plugin(app: &mut App) {
+    if !app.is_plugin_added::<RapierPhysicsPlugin<NoUserData>>() {
+        app.add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(10.0)); // Physics plugin
+    }
+    app.add_systems(OnEnter(STATE), setup)
+        .add_systems(Update, (controls, throw_rocks, clean_up_rocks, score_handler, death_handler)
+            .run_if(in_state(STATE)).in_set(GameplaySet))
+        .add_systems(OnEnter(PauseState::Paused), suspend_physics.run_if(in_state(STATE)))
+        .add_systems(OnEnter(RunState::GameOver), suspend_physics.run_if(in_state(STATE)))
+        // always resume, the game may already have been left when the pause or game over ends
+        .add_systems(OnExit(PauseState::Paused), resume_physics)
+        .add_systems(OnExit(RunState::GameOver), resume_physics)
+        .add_systems(OnExit(STATE), despawn_screen::<OnGameScreen>);
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server:Res<AssetServer>,
+) {
+    // floor
+    commands.spawn(FloorBundle::new(asset_server.load("m_brick.png")))
+        .insert(Collider::cuboid(PLAYFIELD.width/2.,FLOOR_HEIGHT/2.))
+        .insert(OnGameScreen);
+    
+    // player
+    commands.spawn(RigidBody::Dynamic)
+        .insert(Collider::ball(130.0))
+        .insert(KinematicCharacterController::default())
+        .insert(SpriteBundle {
+            texture: asset_server.load("rustacean-flat-happy.png"),
+            transform: Transform::from_scale(Vec3::new(0.25,0.25,1.)).with_translation(Vec3::new(-200., FLOOR_TOP + 36., 0.)),
+            ..Default::default()
+        })
+        .insert(GravityScale(5.0))
+        .insert(Velocity {
+            linvel:Vec2::new(0.,0.),
+            ..Default::default()
+        })
+        .insert(Player)
+        .insert(OnGameScreen);
+    
+    
+    commands.insert_resource(RockTime{
+        timer:Timer::from_seconds(1.7, TimerMode::Repeating),
+    });
+
+    commands.insert_resource(ScoreTimer{
+        timer:Timer::from_seconds(1., TimerMode::Repeating),
+    });
+    commands.insert_resource(Score::default());
+    // a single hit ends the run
+    commands.insert_resource(Health{health:1});
+
+    commands.spawn((
+        ScoreText,
+        OnGameScreen,
+        hud_text(
+            "Score: ",
+            TextStyle{
+                font_size:40.,
+                color:Color::rgb(0.5, 0.5, 1.0),
+                ..default()
+            }
+        ),
+    ));
+}
+
+fn controls(input:Res<ButtonInput<Action>>,mut query:Query<&mut Velocity, With<Player>>, mut sfx:EventWriter<PlaySfx>) {
+    let mut player = query.single_mut();
+    if input.just_pressed(Action::Jump) {
+        player.linvel = Vec2::new(0., 300.);
+        sfx.send(PlaySfx(Sfx::Jump));
+    }
+}
+
+fn throw_rocks(mut commands:Commands, time: Res<Time>, mut rock_time: ResMut<RockTime>,
+    asset_server:Res<AssetServer>, health: Res<Health>, mut rng: ResMut<GameRng>) {
+    rock_time.timer.tick(time.delta());
+
+    if rock_time.timer.just_finished() && !health.is_dead() {
+        commands.spawn(ObstacleBundle::new(asset_server.load("harmful1.png"), &mut rng))
+            .insert(Sensor)
+            .insert(OnGameScreen);
+    }
+}
+
+fn clean_up_rocks(mut commands:Commands, query:Query<(Entity, &Transform), With<Rock>>) {
+    for (entity, transform) in &query {
+        if transform.translation.x < PLAYFIELD.left() - ROCK_MARGIN {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn score_handler(time: Res<Time>, mut score_timer: ResMut<ScoreTimer>, mut score: ResMut<Score>, health: Res<Health>) {
+    score_timer.timer.tick(time.delta());
+
+    if score_timer.timer.just_finished() && !health.is_dead() {
+        score.score += 1;
+    }
+}
+
+fn death_handler(mut health:ResMut<Health>, rapier_context:Res<RapierContext>, query:Query<Entity, With<Player>>,
+    mut run_ended: EventWriter<RunEnded>, mut sfx: EventWriter<PlaySfx>) {
+    let entity = query.single();
+    for (_collider1, _collider2, intersecting) in rapier_context.intersection_pairs_with(entity) {
+        // one crash is enough, even when touching two rocks at once
+        if intersecting && !health.is_dead() {
+            health.health = 0;
+            run_ended.send(RunEnded { won: false });
+            sfx.send(PlaySfx(Sfx::Crash));
+        }
+    }
+}
+
+fn suspend_physics(mut rapier_config: ResMut<RapierConfiguration>) {
+    rapier_config.physics_pipeline_active = false;
+}
+
+fn resume_physics(mut rapier_config: ResMut<RapierConfiguration>) {
+    rapier_config.physics_pipeline_active = true;
+}
@@ -0,0 +1,231 @@
+// Command line options of the launcher and the standalone games, run with `--help` for the list.
+// Options given here win over the saved settings for this run only.
+
+use std::path::PathBuf;
+use std::process;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::RapierDebugRenderPlugin;
+
+use crate::difficulty::{Difficulty, RunDifficulty};
+use crate::headless::HeadlessGame;
+use crate::minigame::{Minigame, MinigameRegistry};
+use crate::replay::{Playback, Replay};
+use crate::rng::{FixedSeed, GameRng};
+use crate::settings::{DisplayMode, Resolution, SettingsOverrides};
+use crate::GameState;
+
+pub const USAGE: &str = "\
+Usage: cargo run [--bin <game>] -- [options]
+
+Options:
+  --game <name>            start in a game instead of the menu, by its id or a word of its title
+                           (e.g. shooter or scramble)
+  --seed <n>               use this seed for every run
+  --difficulty <level>     easy, normal or hard
+  --size <width>x<height>  window size, e.g. 1600x900
+  --fullscreen             start in fullscreen
+  --debug-physics          draw the physics colliders
+  --replay <file>          watch a replay file
+  --headless <frames>      play the game without a window for up to this many frames (or until the
+                           run ends) and print the result as JSON
+  -h, --help               show this
+
+--game, --replay and --headless only work with the launcher.";
+
+#[derive(Resource, Clone, Default, PartialEq, Debug)]
+pub struct CliArgs {
+    pub game: Option<String>,
+    pub seed: Option<u64>,
+    pub difficulty: Option<Difficulty>,
+    pub size: Option<Resolution>,
+    pub fullscreen: bool,
+    pub debug_physics: bool,
+    pub replay: Option<PathBuf>,
+    pub headless_frames: Option<usize>,
+    pub help: bool,
+}
+
+// What the launcher starts in instead of the menu
+pub struct Launch {
+    pub state: GameState,
+    // present when watching a replay
+    pub replay: Option<Replay>,
+}
+
+impl CliArgs {
+    // Parses the process's arguments. Bad ones print the error and exit, `--help` prints the usage
+    // and exits.
+    pub fn from_env() -> Self {
+        let args = Self::parse(std::env::args().skip(1)).unwrap_or_else(|error| exit_with_error(&error));
+        if args.help {
+            println!("{USAGE}");
+            process::exit(0);
+        }
+        args
+    }
+
+    // Options can be given as `--seed 1234` or `--seed=1234`
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = CliArgs::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{name} needs a value"))
+            };
+            match name.as_str() {
+                "--game" => parsed.game = Some(value()?),
+                "--seed" => parsed.seed = Some(parse_number(&name, &value()?)?),
+                "--difficulty" => parsed.difficulty = Some(parse_difficulty(&value()?)?),
+                "--size" => parsed.size = Some(parse_size(&value()?)?),
+                "--fullscreen" => parsed.fullscreen = true,
+                "--debug-physics" => parsed.debug_physics = true,
+                "--replay" => parsed.replay = Some(PathBuf::from(value()?)),
+                "--headless" => parsed.headless_frames = Some(parse_number(&name, &value()?)?),
+                "-h" | "--help" => parsed.help = true,
+                _ => return Err(format!("unknown option {name}")),
+            }
+        }
+        if parsed.game.is_some() && parsed.replay.is_some() {
+            return Err("--game and --replay can't be used together, a replay knows its game".to_string());
+        }
+        Ok(parsed)
+    }
+
+    // Overrides the saved settings with the ones given, add after the app's other plugins
+    pub fn apply(&self, app: &mut App) {
+        // kept apart so they aren't saved with the settings
+        app.insert_resource(SettingsOverrides {
+            display_mode: self.fullscreen.then_some(DisplayMode::Fullscreen),
+            resolution: self.size,
+            difficulty: self.difficulty,
+        });
+        if let Some(seed) = self.seed {
+            app.insert_resource(FixedSeed(Some(seed)));
+        }
+        if let Some(difficulty) = self.difficulty {
+            app.insert_resource(difficulty);
+        }
+        if let Some(size) = self.size {
+            app.insert_resource(size);
+        }
+        if self.fullscreen {
+            app.insert_resource(DisplayMode::Fullscreen);
+        }
+        if self.debug_physics && !app.is_plugin_added::<RapierDebugRenderPlugin>() {
+            app.add_plugins(RapierDebugRenderPlugin::default());
+        }
+    }
+
+    // The game or replay to start in, `None` for the menu
+    pub fn launch(&self, registry: &MinigameRegistry) -> Result<Option<Launch>, String> {
+        if let Some(name) = &self.game {
+            let game = find_game(registry, name)?;
+            return Ok(Some(Launch { state: game.state(), replay: None }));
+        }
+        if let Some(path) = &self.replay {
+            let replay = Replay::load(path).ok_or_else(|| format!("couldn't read a replay from {}", path.display()))?;
+            let game = registry
+                .by_id(&replay.game)
+                .ok_or_else(|| format!("the replay is of {}, which isn't a game here", replay.game))?;
+            return Ok(Some(Launch { state: game.state(), replay: Some(replay) }));
+        }
+        Ok(None)
+    }
+}
+
+// Plays the game picked with `--game` or `--replay` without a window for `headless_frames`, or
+// until the run ends, and returns the result as a line of JSON. `register` adds the games.
+pub fn run_headless(args: &CliArgs, register: impl FnOnce(&mut App)) -> Result<String, String> {
+    let max_frames = args.headless_frames.unwrap_or_default();
+    let mut app = HeadlessGame::build_app(args.seed.unwrap_or_else(rand::random));
+    register(&mut app);
+    // there's no window to draw the colliders in
+    CliArgs { debug_physics: false, ..args.clone() }.apply(&mut app);
+    let launch = args
+        .launch(app.world.resource::<MinigameRegistry>())?
+        .ok_or("--headless needs a --game or --replay to play")?;
+
+    let mut game = HeadlessGame::start(app, launch.state);
+    if let Some(replay) = launch.replay {
+        game.world().insert_resource(Playback::new(replay));
+    }
+    let mut frames = 0;
+    while frames < max_frames && !game.is_game_over() {
+        game.step();
+        frames += 1;
+    }
+
+    let id = game_id(launch.state);
+    let seed = game.world().resource::<GameRng>().seed();
+    let difficulty = game.world().resource::<RunDifficulty>().0;
+    Ok(format!(
+        r#"{{"game":"{id}","seed":{seed},"difficulty":"{}","frames":{frames},"score":{},"health":{},"game_over":{}}}"#,
+        difficulty.label(),
+        json_number(game.score()),
+        json_number(game.health()),
+        game.is_game_over(),
+    ))
+}
+
+pub fn exit_with_error(error: &str) -> ! {
+    eprintln!("{error}\nRun with --help to see the options.");
+    process::exit(2);
+}
+
+// `name` is a game's id or one of the words of its title
+fn find_game<'a>(registry: &'a MinigameRegistry, name: &str) -> Result<&'a dyn Minigame, String> {
+    if let Some(game) = registry.by_id(name) {
+        return Ok(game);
+    }
+    let name = name.to_lowercase();
+    let matches: Vec<&dyn Minigame> = registry
+        .iter()
+        .filter(|game| game.name().to_lowercase().split_whitespace().any(|word| word == name))
+        .collect();
+    let ids = || registry.iter().map(|game| game_id(game.state())).collect::<Vec<_>>().join(", ");
+    match matches[..] {
+        [game] => Ok(game),
+        [] => Err(format!("there's no game called {name}, the games are {}", ids())),
+        _ => Err(format!("{name} could be more than one game, the games are {}", ids())),
+    }
+}
+
+fn game_id(state: GameState) -> &'static str {
+    match state {
+        GameState::Playing(id) | GameState::Restarting(id) => id,
+        _ => "",
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("{name} needs a number, not {value}"))
+}
+
+fn parse_difficulty(value: &str) -> Result<Difficulty, String> {
+    Difficulty::ALL
+        .into_iter()
+        .find(|difficulty| difficulty.label().eq_ignore_ascii_case(value))
+        .ok_or_else(|| format!("--difficulty needs easy, normal or hard, not {value}"))
+}
+
+// `1600x900`
+fn parse_size(value: &str) -> Result<Resolution, String> {
+    let error = || format!("--size needs a size like 1600x900, not {value}");
+    let (width, height) = value.split_once('x').ok_or_else(error)?;
+    match (width.parse(), height.parse()) {
+        (Ok(width), Ok(height)) if width > 0 && height > 0 => Ok(Resolution(width, height)),
+        _ => Err(error()),
+    }
+}
+
+fn json_number(value: Option<i32>) -> String {
+    value.map_or("null".to_string(), |value| value.to_string())
+}
@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+
+// Axis-aligned bounding box check between two rectangles given by their centre and size
+pub fn check_collision(pos1: Vec2, size1: Vec2, pos2: Vec2, size2: Vec2) -> bool {
+    let left1   = pos1.x - size1.x/2.0;
+    let right1  = pos1.x + size1.x/2.0;
+    let top1    = pos1.y + size1.y/2.0;
+    let bottom1 = pos1.y - size1.y/2.0;
+
+    let left2   = pos2.x - size2.x/2.0;
+    let right2  = pos2.x + size2.x/2.0;
+    let top2    = pos2.y + size2.y/2.0;
+    let bottom2 = pos2.y - size2.y/2.0;
+
+    // If one rectangle is on the left side of the other
+    if right1 <= left2 || right2 <= left1 {
+        return false;
+    }
+
+    // If one rectangle is above the other
+    if bottom1 >= top2 || bottom2 >= top1 {
+        return false;
+    }
+
+    // If they are neither, then they must be colliding.
+    true
+}
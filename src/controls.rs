@@ -0,0 +1,168 @@
+// Games read `ButtonInput<Action>` instead of the keyboard. Every game lists the actions it uses
+// with its default keys, the player can rebind them from the launcher, and any gamepad works too.
+
+use std::collections::BTreeMap;
+
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::minigame::MinigameRegistry;
+use crate::storage::{load_ron, save_ron};
+use crate::GameState;
+
+const CONTROLS_FILE: &str = "controls.ron";
+// How far a stick has to be pushed to count as a move
+const STICK_THRESHOLD: f32 = 0.5;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    Fire,
+    Jump,
+    Pause,
+}
+
+impl Action {
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::Fire => "Fire",
+            Action::Jump => "Jump",
+            Action::Pause => "Pause",
+        }
+    }
+
+    // Gamepad buttons are not rebindable, every game uses the same layout
+    fn gamepad_buttons(self) -> &'static [GamepadButtonType] {
+        match self {
+            Action::MoveLeft => &[GamepadButtonType::DPadLeft],
+            Action::MoveRight => &[GamepadButtonType::DPadRight],
+            Action::Fire => &[GamepadButtonType::South, GamepadButtonType::RightTrigger2],
+            Action::Jump => &[GamepadButtonType::South],
+            Action::Pause => &[GamepadButtonType::Start],
+        }
+    }
+}
+
+// Every game can be paused with P
+pub const PAUSE_KEY: (Action, KeyCode) = (Action::Pause, KeyCode::KeyP);
+
+// Keys the player rebound, by game id. Anything not in here uses the game's default.
+#[derive(Resource, Serialize, Deserialize, Default)]
+pub struct KeyBindings {
+    games: BTreeMap<String, BTreeMap<Action, KeyCode>>,
+}
+
+impl KeyBindings {
+    pub fn load() -> Self {
+        load_ron(CONTROLS_FILE).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        save_ron(CONTROLS_FILE, self);
+    }
+
+    // The game's defaults with the player's changes applied, in the order the game listed them
+    pub fn for_game(&self, game: &str, defaults: &[(Action, KeyCode)]) -> Vec<(Action, KeyCode)> {
+        let rebound = self.games.get(game);
+        defaults
+            .iter()
+            .chain([&PAUSE_KEY])
+            .map(|&(action, key)| {
+                (action, rebound.and_then(|keys| keys.get(&action)).copied().unwrap_or(key))
+            })
+            .collect()
+    }
+
+    pub fn rebind(&mut self, game: &str, action: Action, key: KeyCode) {
+        self.games.entry(game.to_string()).or_default().insert(action, key);
+    }
+
+    pub fn reset(&mut self) {
+        self.games.clear();
+    }
+}
+
+// Short name of a key for the controls screen, e.g. "A" rather than "KeyA"
+pub fn key_name(key: KeyCode) -> String {
+    let name = format!("{key:?}");
+    ["Key", "Digit"]
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+        .map_or_else(|| name.clone(), str::to_string)
+}
+
+// Updated right after the keyboard and gamepads are read, while a game is running
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ActionInputSet;
+
+pub fn controls_plugin(app: &mut App) {
+    app.insert_resource(KeyBindings::load())
+        .init_resource::<ButtonInput<Action>>()
+        .configure_sets(PreUpdate, ActionInputSet.after(InputSystem))
+        .add_systems(PreUpdate, update_actions.in_set(ActionInputSet));
+}
+
+fn update_actions(
+    game_state: Res<State<GameState>>,
+    registry: Option<Res<MinigameRegistry>>,
+    bindings: Res<KeyBindings>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    mut actions: ResMut<ButtonInput<Action>>,
+) {
+    // outside of a game no action is bound
+    let keys = match *game_state.get() {
+        GameState::Playing(id) => registry
+            .as_ref()
+            .and_then(|registry| registry.by_id(id))
+            .map_or_else(Vec::new, |game| bindings.for_game(id, game.controls())),
+        _ => Vec::new(),
+    };
+
+    let held = |action: Action| {
+        let key_held = keys.iter().any(|&(bound, key)| bound == action && keyboard.pressed(key));
+        let button_held = gamepads.iter().any(|gamepad| {
+            action
+                .gamepad_buttons()
+                .iter()
+                .any(|&button| gamepad_buttons.pressed(GamepadButton::new(gamepad, button)))
+        });
+        let stick_x = gamepads
+            .iter()
+            .filter_map(|gamepad| axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX)))
+            .find(|x| x.abs() >= STICK_THRESHOLD)
+            .unwrap_or(0.0);
+        let stick_held = match action {
+            Action::MoveLeft => stick_x <= -STICK_THRESHOLD,
+            Action::MoveRight => stick_x >= STICK_THRESHOLD,
+            _ => false,
+        };
+        key_held || button_held || stick_held
+    };
+
+    let now_held: Vec<Action> = keys
+        .iter()
+        .map(|&(action, _)| action)
+        .filter(|&action| held(action))
+        .collect();
+    set_held(&mut actions, &now_held);
+}
+
+// Presses and releases `input` so exactly `held` is down, keeping `just_pressed` and
+// `just_released` right for this frame
+pub fn set_held(input: &mut ButtonInput<Action>, held: &[Action]) {
+    input.clear();
+    let released: Vec<Action> = input.get_pressed().filter(|action| !held.contains(action)).copied().collect();
+    for action in released {
+        input.release(action);
+    }
+    for &action in held {
+        input.press(action);
+    }
+}
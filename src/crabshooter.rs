@@ -0,0 +1,1599 @@
+use std::collections::HashSet;
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+use bevy::{prelude::*};
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::utils::{default, BoxedFuture};
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::audio::{PlaySfx, Sfx};
+use crate::collision::check_collision;
+use crate::controls::Action;
+use crate::game_over::RunEnded;
+use crate::hud::{hud_text, Health, HealthText, Score, ScoreText};
+use crate::player::Player;
+use crate::playfield::PLAYFIELD;
+use crate::rng::{reseed_rng, GameRng};
+use crate::minigame::Minigame;
+use crate::{despawn_screen, GameState, GameplaySet, OnGameScreen};
+
+pub const STATE: GameState = GameState::Playing("crabshooter");
+
+pub struct CrabShooter;
+
+impl Minigame for CrabShooter {
+    fn name(&self) -> &'static str {
+        "Crab Shooter"
+    }
+
+    fn description(&self) -> &'static str {
+        "Shoot the Corros before they shoot you"
+    }
+
+    fn icon(&self) -> &'static str {
+        "textures/corro.png"
+    }
+
+    fn state(&self) -> GameState {
+        STATE
+    }
+
+    fn controls(&self) -> &'static [(Action, KeyCode)] {
+        &[
+            (Action::MoveLeft, KeyCode::ArrowLeft),
+            (Action::MoveRight, KeyCode::ArrowRight),
+            (Action::Fire, KeyCode::Space),
+        ]
+    }
+
+    fn music(&self) -> Option<&'static str> {
+        Some("audio/crabshooter_music.wav")
+    }
+
+    fn is_loaded(&self, world: &World) -> bool {
+        world
+            .get_resource::<WaveTableHandle>()
+            .is_some_and(|handle| world.resource::<Assets<WaveTable>>().contains(&handle.0))
+    }
+
+    fn build(&self, app: &mut App) {
+        crabshooter_plugin(app);
+    }
+}
+
+// GAME THREE PLUGIN
+fn crabshooter_plugin(app: &mut App) {
+    app.init_asset::<WaveTable>()
+        .register_asset_loader(WaveTableLoader)
+        // loaded up front so it's ready by the time a run starts
+        .add_systems(Startup, load_wave_table)
+        .add_systems(OnEnter(STATE), setup_game.after(reseed_rng))
+        .configure_sets(Update, (ShooterSet::Move, ShooterSet::Collide, ShooterSet::Spawn).chain()
+            .run_if(in_state(STATE)).in_set(GameplaySet))
+        // Every movement pattern has its own component and system
+        .add_systems(Update, (move_player, shoot_projectile, move_projectiles, enemy_projectile, move_enemy_projectiles,
+                              strafe, sine_drift, dive, move_formations, move_boss, boss_attack,
+                              steer_homing_projectiles, move_pickups, wear_off_shield)
+            .in_set(ShooterSet::Move))
+        // chained so a projectile used up by one collision is gone before the next one looks at it
+        .add_systems(Update, (kill_enemy, hurt_boss, projectile_collision, hurt_player, ram_player, collect_pickups)
+            .chain().in_set(ShooterSet::Collide))
+        .add_systems(Update, (kill_player, destroy_projectiles, destroy_enemy_projectiles, clean_up_enemies,
+                              clean_up_formations, run_waves, spawn_score_bosses, update_boss_bar, tint_player,
+                              update_weapon_text)
+            .in_set(ShooterSet::Spawn))
+        .add_systems(Update, update_wave_texts.run_if(in_state(STATE)).run_if(resource_exists_and_changed::<Waves>))
+        .add_systems(OnExit(STATE), despawn_screen::<OnGameScreen>);
+}
+// Each frame things move and shoot first, then collide, then the leftovers are cleaned up and new
+// enemies come in. Despawns from one step are applied before the next, so results don't depend on
+// which order the systems inside a step happen to run in
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+enum ShooterSet {
+    Move,
+    Collide,
+    Spawn,
+}
+// player projectile
+#[derive(Component)]
+pub struct Projectile;
+// enemy projectile
+#[derive(Component)]
+pub struct EnemyProjectile;
+// player projectiles that fly through enemies instead of stopping at the first one
+#[derive(Component)]
+pub struct Piercing;
+// a big shot fired by letting go of a held Fire, it pierces and hurts bosses more
+#[derive(Component)]
+pub struct Charged;
+// Which way an enemy projectile flies, in pixels per second. Projectiles without one fall
+// straight down.
+#[derive(Component)]
+pub struct ProjectileVelocity(pub Vec2);
+// How fast the player is moving, enemies aim ahead of it
+#[derive(Component, Default)]
+pub struct PlayerVelocity(pub Vec2);
+// The Enemy object
+#[derive(Component)]
+pub struct Enemy;
+
+// What the player's gun does, upgraded by picking up power-ups. Every run starts with a single
+// shot every half second.
+#[derive(Resource)]
+pub struct Weapon {
+    pub shot: Shot,
+    pub rapid_fire: bool,
+    pub piercing: bool,
+    // holding Fire charges a big shot that's fired when it's let go
+    pub charge: bool,
+    // limits how often the player can shoot
+    reload: Timer,
+    // how long Fire has been held, for the charged shot
+    charging: f32,
+}
+
+impl Default for Weapon {
+    fn default() -> Self {
+        Self {
+            shot: Shot::Single,
+            rapid_fire: false,
+            piercing: false,
+            charge: false,
+            reload: Timer::from_seconds(RELOAD_TIME, TimerMode::Once),
+            charging: 0.0,
+        }
+    }
+}
+
+impl Weapon {
+    // 1 to start with, one more for every upgrade
+    pub fn level(&self) -> u32 {
+        let shot = match self.shot {
+            Shot::Single => 1,
+            Shot::Double => 2,
+            Shot::Spread => 3,
+        };
+        shot + self.rapid_fire as u32 + self.piercing as u32 + self.charge as u32
+    }
+
+    pub fn is_charged(&self) -> bool {
+        self.charge && self.charging >= CHARGE_TIME
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Shot {
+    Single,
+    // two side by side
+    Double,
+    // three fanned out
+    Spread,
+}
+
+// Dropped now and then by enemies as they die, caught by touching it
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PickUp {
+    DoubleShot,
+    TripleShot,
+    RapidFire,
+    Piercing,
+    ChargeShot,
+    // blocks every hit for a while
+    Shield,
+    // clears every enemy projectile on screen
+    Bomb,
+}
+
+impl PickUp {
+    pub const ALL: [PickUp; 7] = [
+        PickUp::DoubleShot,
+        PickUp::TripleShot,
+        PickUp::RapidFire,
+        PickUp::Piercing,
+        PickUp::ChargeShot,
+        PickUp::Shield,
+        PickUp::Bomb,
+    ];
+
+    // shown on the pick-up so they can be told apart
+    fn letter(self) -> &'static str {
+        match self {
+            PickUp::DoubleShot => "2",
+            PickUp::TripleShot => "3",
+            PickUp::RapidFire => "R",
+            PickUp::Piercing => "P",
+            PickUp::ChargeShot => "C",
+            PickUp::Shield => "S",
+            PickUp::Bomb => "B",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            PickUp::Shield => Color::rgb(0.3, 0.7, 1.0),
+            PickUp::Bomb => Color::rgb(1.0, 0.3, 0.2),
+            // the weapon upgrades
+            _ => Color::rgb(1.0, 0.8, 0.2),
+        }
+    }
+}
+
+// Put on the player while the shield is up
+#[derive(Component)]
+pub struct PlayerShield(pub Timer);
+
+// Weapon level in the HUD
+#[derive(Component)]
+struct WeaponText;
+
+// The kinds of Corro, each one moves and shoots its own way
+#[derive(Component, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EnemyKind {
+    // stays where it spawned
+    Corro,
+    // slides from wall to wall
+    Strafer,
+    // weaves from side to side while sinking
+    Drifter,
+    // hovers for a moment, then dives at the player
+    Diver,
+    // comes in rows that march together, like Space Invaders
+    Marcher,
+}
+
+impl EnemyKind {
+    // tint, so the kinds can be told apart
+    fn color(self) -> Color {
+        match self {
+            EnemyKind::Corro => Color::WHITE,
+            EnemyKind::Strafer => Color::rgb(0.6, 0.8, 1.0),
+            EnemyKind::Drifter => Color::rgb(0.6, 1.0, 0.6),
+            EnemyKind::Diver => Color::rgb(1.0, 0.5, 0.4),
+            EnemyKind::Marcher => Color::rgb(1.0, 0.9, 0.4),
+        }
+    }
+
+    fn movement(self) -> Movement {
+        match self {
+            EnemyKind::Corro => Movement::Still,
+            EnemyKind::Strafer => Movement::Strafe,
+            EnemyKind::Drifter => Movement::Drift,
+            EnemyKind::Diver => Movement::Dive,
+            EnemyKind::Marcher => Movement::Formation { rows: 2, columns: 5 },
+        }
+    }
+
+    fn gun(self) -> EnemyGun {
+        match self {
+            EnemyKind::Corro => EnemyGun::new(FirePattern::Straight, 2.0),
+            EnemyKind::Strafer => EnemyGun::new(FirePattern::Aimed, 2.5),
+            EnemyKind::Drifter => EnemyGun::new(FirePattern::Spread { shots: 3, angle: 40.0 }, 3.0),
+            EnemyKind::Diver => EnemyGun::new(FirePattern::Burst { shots: 3, interval: 0.15 }, 3.0),
+            // there are a lot of them
+            EnemyKind::Marcher => EnemyGun::new(FirePattern::Straight, 5.0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FirePattern {
+    // one shot straight down
+    Straight,
+    // one shot at where the player is heading
+    Aimed,
+    // `shots` shots fanned out over `angle` degrees, centered on straight down
+    Spread { shots: u32, angle: f32 },
+    // `shots` aimed shots, `interval` seconds apart
+    Burst { shots: u32, interval: f32 },
+}
+
+// Every enemy reloads on its own, firing its pattern each time `reload` finishes
+#[derive(Component)]
+pub struct EnemyGun {
+    pub pattern: FirePattern,
+    pub reload: Timer,
+    // shots left in the current burst and the time until the next one
+    burst: Option<(u32, Timer)>,
+}
+
+impl EnemyGun {
+    pub fn new(pattern: FirePattern, reload_secs: f32) -> Self {
+        Self {
+            pattern,
+            reload: Timer::from_seconds(reload_secs, TimerMode::Repeating),
+            burst: None,
+        }
+    }
+}
+
+// How an enemy moves, each pattern is a component with its own system
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Movement {
+    Still,
+    Strafe,
+    Drift,
+    Dive,
+    // a block of `rows` by `columns` enemies
+    Formation { rows: usize, columns: usize },
+}
+
+// Moves sideways at `speed`, turning around at the walls
+#[derive(Component)]
+pub struct Strafe {
+    pub speed: f32,
+}
+
+// Sways around `center_x` while sinking at `fall_speed`
+#[derive(Component)]
+pub struct SineDrift {
+    pub center_x: f32,
+    pub amplitude: f32,
+    // swings per second
+    pub frequency: f32,
+    pub fall_speed: f32,
+    phase: f32,
+}
+
+impl SineDrift {
+    pub fn new(center_x: f32) -> Self {
+        Self {
+            center_x,
+            amplitude: 150.0,
+            frequency: 0.4,
+            fall_speed: 20.0,
+            phase: 0.0,
+        }
+    }
+}
+
+// Waits for `wait` to finish, then flies in a straight line at where the player was
+#[derive(Component)]
+pub struct Dive {
+    pub wait: Timer,
+    pub speed: f32,
+    velocity: Option<Vec2>,
+}
+
+impl Dive {
+    pub fn new(wait_secs: f32) -> Self {
+        Self {
+            wait: Timer::from_seconds(wait_secs, TimerMode::Once),
+            speed: 350.0,
+            velocity: None,
+        }
+    }
+}
+
+// A block of enemies moving as one: sideways until one of them reaches a wall, then a step down
+// and back the other way
+#[derive(Component)]
+pub struct Formation {
+    pub center: Vec2,
+    // 1 for right, -1 for left
+    pub direction: f32,
+    // from the center to the outer edge of the outermost column
+    pub half_width: f32,
+}
+
+// An enemy flying in a formation, `offset` from its center
+#[derive(Component)]
+pub struct InFormation {
+    pub formation: Entity,
+    pub offset: Vec2,
+}
+
+// A big Corro that takes many hits, attacking harder as its health bar empties
+#[derive(Component)]
+pub struct Boss {
+    pub health: i32,
+    pub attack: Timer,
+    // 1 for right, -1 for left
+    direction: f32,
+}
+
+impl Boss {
+    pub const MAX_HEALTH: i32 = BOSS_SEGMENTS as i32 * BOSS_SEGMENT_HEALTH;
+    // where its claws are, from its center. Hitting them does `WEAK_POINT_DAMAGE`.
+    pub const WEAK_POINTS: [Vec2; 2] = [Vec2::new(-95.0, -45.0), Vec2::new(95.0, -45.0)];
+
+    pub fn new() -> Self {
+        Self {
+            health: Self::MAX_HEALTH,
+            attack: Timer::from_seconds(BOSS_ATTACK_TIME, TimerMode::Repeating),
+            direction: 1.0,
+        }
+    }
+
+    // one phase per segment of the health bar
+    pub fn phase(&self) -> BossPhase {
+        let lost_segments = (Self::MAX_HEALTH - self.health) / BOSS_SEGMENT_HEALTH;
+        match lost_segments {
+            0 => BossPhase::Fan,
+            1 => BossPhase::Homing,
+            _ => BossPhase::Summon,
+        }
+    }
+}
+
+impl Default for Boss {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BossPhase {
+    // wide fans of shots
+    Fan,
+    // shots that turn towards the player
+    Homing,
+    // calls in divers, with a smaller fan
+    Summon,
+}
+
+// anything the player has to beat to clear a wave
+type AnyEnemy = Or<(With<Enemy>, With<Boss>)>;
+
+// Enemy projectiles that turn towards the player at `turn_rate` radians a second until `time`
+// runs out
+#[derive(Component)]
+pub struct Homing {
+    pub turn_rate: f32,
+    pub time: Timer,
+}
+
+// The boss's health bar at the top of the screen, one segment per phase
+#[derive(Component)]
+struct BossBar;
+
+// How full one segment of the bar is
+#[derive(Component)]
+struct BossBarFill(usize);
+
+const PLAYER_SIZE: Vec2 = Vec2::new(0.25*460.0, 0.25*307.0);
+const RELOAD_TIME: f32 = 0.5;
+const RAPID_FIRE_RELOAD_TIME: f32 = 0.25;
+// how far apart double shots are
+const DOUBLE_SHOT_GAP: f32 = 30.0;
+// degrees between the shots of a triple spread
+const SPREAD_ANGLE: f32 = 15.0;
+// how long Fire has to be held for a charged shot
+const CHARGE_TIME: f32 = 1.0;
+// charged shots are this much bigger
+const CHARGED_SCALE: f32 = 2.5;
+pub const CHARGED_DAMAGE: i32 = 5;
+const PICKUP_SIZE: Vec2 = Vec2::new(40.0, 40.0);
+const PICKUP_SPEED: f32 = 120.0;
+const PICKUP_DROP_CHANCE: f64 = 0.15;
+const SHIELD_TIME: f32 = 8.0;
+const PLAYER_STARTING_POSITION: Vec3 = Vec3::new(0.0, -PLAYFIELD.height / 2.0 + 160.0, 1.0);
+const ENEMY_STARTING_HEIGHT: f32 = PLAYFIELD.height / 2.0 - 160.0;
+const ENEMY_SIZE: Vec2 = Vec2::new(0.25*315.0, 0.25*250.0);
+const STRAFE_SPEED: f32 = 150.0;
+// how long a diver hovers before diving
+const DIVE_WAIT: f32 = 1.5;
+const FORMATION_SPACING: Vec2 = Vec2::new(110.0, 80.0);
+const FORMATION_SPEED: f32 = 80.0;
+const FORMATION_STEP: f32 = 30.0;
+const BOSS_SIZE: Vec2 = Vec2::new(3.0*ENEMY_SIZE.x, 3.0*ENEMY_SIZE.y);
+const BOSS_COLOR: Color = Color::rgb(1.0, 0.4, 0.7);
+const BOSS_HEIGHT: f32 = PLAYFIELD.height / 2.0 - 170.0;
+const BOSS_SPEED: f32 = 100.0;
+const BOSS_SEGMENTS: usize = 3;
+const BOSS_SEGMENT_HEALTH: i32 = 20;
+const BOSS_ATTACK_TIME: f32 = 2.0;
+// the boss only calls in more divers while there are fewer enemies than this
+const BOSS_MAX_MINIONS: usize = 4;
+const WEAK_POINT_SIZE: Vec2 = Vec2::new(50.0, 50.0);
+const WEAK_POINT_DAMAGE: i32 = 3;
+const BOSS_SCORE: i32 = 50;
+const WAVES_FILE: &str = "crabshooter/waves.ron";
+// how long "Wave N" is shown before the wave comes in
+const BANNER_TIME: f32 = 2.0;
+
+// Crab Shooter's waves, loaded from `WAVES_FILE`
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct WaveTable {
+    pub waves: Vec<WaveDef>,
+    // a boss also comes in as the score reaches each of these
+    #[serde(default)]
+    pub boss_scores: Vec<i32>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct WaveDef {
+    pub groups: Vec<SpawnGroup>,
+    // a boss comes in with the wave, which isn't cleared until it's beaten
+    #[serde(default)]
+    pub boss: bool,
+}
+
+// Enemies of one kind that come in one after the other
+#[derive(Deserialize, Clone, Debug)]
+pub struct SpawnGroup {
+    pub kind: EnemyKind,
+    #[serde(default = "one")]
+    pub count: u32,
+    // seconds into the wave
+    #[serde(default)]
+    pub delay: f32,
+    // seconds between enemies
+    #[serde(default)]
+    pub interval: f32,
+    // random if left out
+    #[serde(default)]
+    pub x: Option<f32>,
+    // the kind's own if left out
+    #[serde(default)]
+    pub movement: Option<Movement>,
+}
+
+fn one() -> u32 {
+    1
+}
+
+#[derive(Resource)]
+struct WaveTableHandle(Handle<WaveTable>);
+
+#[derive(Default)]
+struct WaveTableLoader;
+
+impl AssetLoader for WaveTableLoader {
+    type Asset = WaveTable;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<WaveTable, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(ron::de::from_bytes(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["waves.ron"]
+    }
+}
+
+// Progress through the waves of the current run
+#[derive(Resource)]
+pub struct Waves {
+    // index into `WaveTable::waves`
+    pub wave: usize,
+    pub phase: WavePhase,
+    // how many of `WaveTable::boss_scores` have been reached
+    pub score_bosses: usize,
+}
+
+pub enum WavePhase {
+    // "Wave N" is shown before the wave comes in
+    Banner(Timer),
+    // seconds since the wave came in and how many of each group have spawned so far
+    Spawning { elapsed: f32, spawned: Vec<u32> },
+    // the last wave has been beaten
+    Won,
+}
+
+impl Default for Waves {
+    fn default() -> Self {
+        Self {
+            wave: 0,
+            phase: WavePhase::Banner(Timer::from_seconds(BANNER_TIME, TimerMode::Once)),
+            score_bosses: 0,
+        }
+    }
+}
+
+// "Wave N" in the middle of the screen between waves
+#[derive(Component)]
+struct WaveBanner;
+
+// Wave counter in the HUD
+#[derive(Component)]
+struct WaveText;
+
+fn setup_game(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    // Every run starts with fresh resources
+    commands.insert_resource(Score::default());
+    commands.insert_resource(Health{health: 3});
+    commands.insert_resource(Weapon::default());
+    commands.insert_resource(Waves::default());
+    // Background
+    commands.spawn((SpriteBundle {
+        texture: asset_server.load("textures/oceanbg.png"),
+        transform: Transform::from_xyz(0.0, 0.0, 0.0),
+        ..default()
+    }, OnGameScreen));
+    // Spawn Player in initial position
+    commands.spawn((SpriteBundle {
+                texture: asset_server.load("textures/rustacean-flat-happy.png"),
+                transform: Transform {
+                    translation: PLAYER_STARTING_POSITION,
+                    ..default()
+                },
+                sprite: Sprite {
+                    custom_size: Some(PLAYER_SIZE),
+                    //color: PADDLE_COLOR,
+                    ..default()
+                },
+                ..default()
+            },
+            Player, PlayerVelocity::default(), OnGameScreen));
+    // healht text
+    commands.spawn((
+        // Create a TextBundle that has a Text with a single section.
+        hud_text(
+            "Health: ",
+            TextStyle {
+                font_size: 30.0,
+                ..default()
+            },
+        ) // Set the justification of the Text
+            .with_text_justify(JustifyText::Center)
+            // Set the style of the TextBundle itself.
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(30.0),
+                left: Val::Px(30.0),
+                ..default()
+            }),
+        HealthText,
+        OnGameScreen,
+    ));
+    // score text
+    commands.spawn((
+        // Create a TextBundle that has a Text with a single section.
+        hud_text(
+            "Score: ",
+            TextStyle {
+                font_size: 30.0,
+                ..default()
+            },
+        ) // Set the justification of the Text
+            .with_text_justify(JustifyText::Center)
+            // Set the style of the TextBundle itself.
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(30.0),
+                right: Val::Px(30.0),
+                ..default()
+            }),
+        ScoreText,
+        OnGameScreen,
+    ));
+    // wave text
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 30.0,
+                ..default()
+            },
+        )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(70.0),
+                right: Val::Px(30.0),
+                ..default()
+            }),
+        WaveText,
+        OnGameScreen,
+    ));
+    // weapon text
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 30.0,
+                ..default()
+            },
+        )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(110.0),
+                right: Val::Px(30.0),
+                ..default()
+            }),
+        WeaponText,
+        OnGameScreen,
+    ));
+    commands.spawn((Text2dBundle {
+        text: Text::from_section("", TextStyle {
+            font_size: 80.0,
+            ..default()
+        }),
+        transform: Transform::from_xyz(0.0, 0.0, 5.0),
+        ..default()
+    }, WaveBanner, OnGameScreen));
+}
+
+// Defines the amount of time that should elapse between each physics step
+// in this case, 60fps
+const TIME_STEP: f32 = 1.0 / 60.0;
+const PLAYER_SPEED: f32 = 300.0;
+fn move_player(input: Res<ButtonInput<Action>>,
+               mut query: Query<(&mut Transform, &mut PlayerVelocity), With<Player>>,){
+    let left_bound = PLAYFIELD.left() + PLAYER_SIZE.x/2.0;
+    let right_bound = -left_bound;
+    let (mut player_transform, mut velocity) = query.single_mut();
+    let old_player_position = player_transform.translation.x;
+    let mut direction = 0.0;
+    if input.pressed(Action::MoveLeft) {
+        direction -= 1.0;
+    }
+    if input.pressed(Action::MoveRight) {
+        direction += 1.0;
+    }
+    let new_player_position = player_transform.translation.x + direction * PLAYER_SPEED * TIME_STEP;
+    player_transform.translation.x = new_player_position;
+    player_transform.translation.x = f32::max(left_bound, f32::min(right_bound, player_transform.translation.x));
+    // standing still against a wall counts as not moving
+    velocity.0.x = (player_transform.translation.x - old_player_position) / TIME_STEP;
+}
+// Shows the banner, spawns the wave's enemies when they're due and moves on to the next wave once
+// they're all gone. Beating the last wave wins the run.
+fn run_waves(mut commands: Commands,
+             asset_server: Res<AssetServer>,
+             time: Res<Time>,
+             handle: Res<WaveTableHandle>,
+             tables: Res<Assets<WaveTable>>,
+             mut waves: ResMut<Waves>,
+             mut rng: ResMut<GameRng>,
+             enemy_query: Query<(), AnyEnemy>,
+             mut run_ended: EventWriter<RunEnded>) {
+    let Some(table) = tables.get(&handle.0) else {
+        return;
+    };
+    let Waves { wave, phase, .. } = &mut *waves;
+    let Some(wave_def) = table.waves.get(*wave) else {
+        return;
+    };
+    match phase {
+        WavePhase::Banner(timer) => {
+            if timer.tick(time.delta()).finished() {
+                *phase = WavePhase::Spawning { elapsed: 0.0, spawned: vec![0; wave_def.groups.len()] };
+                if wave_def.boss {
+                    spawn_boss(&mut commands, &asset_server, 0.0);
+                }
+            }
+        }
+        WavePhase::Spawning { elapsed, spawned } => {
+            *elapsed += time.delta_seconds();
+            let mut spawned_now = false;
+            for (group, spawned) in wave_def.groups.iter().zip(spawned.iter_mut()) {
+                while *spawned < group.count && *elapsed >= group.delay + group.interval * *spawned as f32 {
+                    let x = group.x.unwrap_or_else(|| {
+                        let random = rng.gen_range(-400.0..=400.0);
+                        random - random % 10.0
+                    });
+                    let movement = group.movement.unwrap_or(group.kind.movement());
+                    spawn_enemies(&mut commands, &asset_server, &mut rng, group.kind, movement, Vec2::new(x, ENEMY_STARTING_HEIGHT));
+                    *spawned += 1;
+                    spawned_now = true;
+                }
+            }
+            let all_spawned = wave_def.groups.iter().zip(spawned.iter()).all(|(group, spawned)| *spawned >= group.count);
+            // enemies spawned this frame aren't in the query yet
+            if all_spawned && !spawned_now && enemy_query.is_empty() {
+                if *wave + 1 < table.waves.len() {
+                    *wave += 1;
+                    *phase = WavePhase::Banner(Timer::from_seconds(BANNER_TIME, TimerMode::Once));
+                } else {
+                    *phase = WavePhase::Won;
+                    run_ended.send(RunEnded { won: true });
+                }
+            }
+        }
+        WavePhase::Won => {}
+    }
+}
+
+fn update_wave_texts(waves: Res<Waves>,
+                     handle: Res<WaveTableHandle>,
+                     tables: Res<Assets<WaveTable>>,
+                     mut banner_query: Query<(&mut Text, &mut Visibility), (With<WaveBanner>, Without<WaveText>)>,
+                     mut hud_query: Query<&mut Text, (With<WaveText>, Without<WaveBanner>)>) {
+    let table = tables.get(&handle.0);
+    let wave_count = table.map_or(0, |table| table.waves.len());
+    let boss_wave = table.and_then(|table| table.waves.get(waves.wave)).is_some_and(|wave| wave.boss);
+    for (mut text, mut visibility) in &mut banner_query {
+        text.sections[0].value = format!("Wave {}", waves.wave + 1);
+        if boss_wave {
+            text.sections[0].value += ": Boss";
+        }
+        *visibility = if matches!(waves.phase, WavePhase::Banner(_)) { Visibility::Inherited } else { Visibility::Hidden };
+    }
+    for mut text in &mut hud_query {
+        text.sections[0].value = format!("Wave: {}/{wave_count}", waves.wave + 1);
+    }
+}
+
+fn load_wave_table(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(WaveTableHandle(asset_server.load(WAVES_FILE)));
+}
+
+// Spawns an enemy of `kind` at `position` that moves with `movement`. Formations are a whole
+// block of enemies centered on `position`.
+pub fn spawn_enemies(commands: &mut Commands, asset_server: &AssetServer, rng: &mut GameRng, kind: EnemyKind,
+                     movement: Movement, position: Vec2) {
+    match movement {
+        Movement::Still => {
+            spawn_corro(commands, asset_server, rng, kind, position);
+        }
+        Movement::Strafe => {
+            let direction = if position.x < 0.0 { 1.0 } else { -1.0 };
+            let enemy = spawn_corro(commands, asset_server, rng, kind, position);
+            commands.entity(enemy).insert(Strafe { speed: direction * STRAFE_SPEED });
+        }
+        Movement::Drift => {
+            let enemy = spawn_corro(commands, asset_server, rng, kind, position);
+            commands.entity(enemy).insert(SineDrift::new(position.x));
+        }
+        Movement::Dive => {
+            let enemy = spawn_corro(commands, asset_server, rng, kind, position);
+            commands.entity(enemy).insert(Dive::new(DIVE_WAIT));
+        }
+        Movement::Formation { rows, columns } => {
+            let size = Vec2::new(columns.max(1) as f32 - 1.0, rows.max(1) as f32 - 1.0) * FORMATION_SPACING;
+            // keep the whole block inside the walls
+            let max_x = PLAYFIELD.right() - size.x / 2.0 - ENEMY_SIZE.x / 2.0;
+            let center = Vec2::new(position.x.clamp(-max_x, max_x), position.y);
+            let formation = commands.spawn((Formation {
+                center,
+                direction: 1.0,
+                half_width: size.x / 2.0 + ENEMY_SIZE.x / 2.0,
+            }, OnGameScreen)).id();
+            for row in 0..rows {
+                for column in 0..columns {
+                    let offset = Vec2::new(column as f32, -(row as f32)) * FORMATION_SPACING - Vec2::new(size.x / 2.0, -size.y / 2.0);
+                    let enemy = spawn_corro(commands, asset_server, rng, kind, center + offset);
+                    commands.entity(enemy).insert(InFormation { formation, offset });
+                }
+            }
+        }
+    }
+}
+
+fn spawn_corro(commands: &mut Commands, asset_server: &AssetServer, rng: &mut GameRng, kind: EnemyKind, position: Vec2) -> Entity {
+    // enemies spawned together don't all fire at once
+    let mut gun = kind.gun();
+    let reload = gun.reload.duration().mul_f32(rng.gen_range(0.0..1.0));
+    gun.reload.set_elapsed(reload);
+    commands.spawn((SpriteBundle {
+        texture: asset_server.load("textures/corro.png"),
+        transform: Transform {
+            translation: position.extend(1.0),
+            ..default()
+        },
+        sprite: Sprite {
+            custom_size: Some(ENEMY_SIZE),
+            color: kind.color(),
+            ..default()
+        },
+        ..default()
+    }, Enemy, kind, gun, OnGameScreen)).id()
+}
+
+fn strafe(mut query: Query<(&mut Transform, &mut Strafe)>) {
+    let right_bound = PLAYFIELD.right() - ENEMY_SIZE.x / 2.0;
+    for (mut transform, mut strafe) in &mut query {
+        transform.translation.x += strafe.speed * TIME_STEP;
+        if transform.translation.x.abs() > right_bound {
+            transform.translation.x = transform.translation.x.clamp(-right_bound, right_bound);
+            strafe.speed = -strafe.speed;
+        }
+    }
+}
+
+fn sine_drift(mut query: Query<(&mut Transform, &mut SineDrift)>) {
+    for (mut transform, mut drift) in &mut query {
+        drift.phase = (drift.phase + TAU * drift.frequency * TIME_STEP) % TAU;
+        transform.translation.x = drift.center_x + drift.amplitude * drift.phase.sin();
+        transform.translation.y -= drift.fall_speed * TIME_STEP;
+    }
+}
+
+fn dive(time: Res<Time>,
+        mut divers: Query<(&mut Transform, &mut Dive), Without<Player>>,
+        player_query: Query<&Transform, With<Player>>) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    for (mut transform, mut dive) in &mut divers {
+        match dive.velocity {
+            Some(velocity) => transform.translation += (velocity * TIME_STEP).extend(0.0),
+            // aim once, so the player can dodge
+            None => if dive.wait.tick(time.delta()).just_finished() {
+                let direction = (player_transform.translation - transform.translation).truncate().normalize_or_zero();
+                dive.velocity = Some(direction * dive.speed);
+            }
+        }
+    }
+}
+
+fn move_formations(mut formations: Query<&mut Formation>,
+                   mut members: Query<(&mut Transform, &InFormation)>) {
+    for mut formation in &mut formations {
+        formation.center.x += formation.direction * FORMATION_SPEED * TIME_STEP;
+        let max_x = PLAYFIELD.right() - formation.half_width;
+        if formation.center.x.abs() > max_x {
+            formation.center.x = formation.center.x.clamp(-max_x, max_x);
+            formation.direction = -formation.direction;
+            formation.center.y -= FORMATION_STEP;
+        }
+    }
+    for (mut transform, member) in &mut members {
+        if let Ok(formation) = formations.get(member.formation) {
+            transform.translation = (formation.center + member.offset).extend(transform.translation.z);
+        }
+    }
+}
+
+// enemies that fly into the player hurt it and are destroyed
+fn ram_player(mut commands: Commands,
+              mut health: ResMut<Health>,
+              player_query: Query<(&Transform, Has<PlayerShield>), With<Player>>,
+              enemy_query: Query<(Entity, &Transform), With<Enemy>>,
+              mut sfx: EventWriter<PlaySfx>) {
+    let Ok((player_transform, shielded)) = player_query.get_single() else {
+        return;
+    };
+    for (enemy, enemy_transform) in &enemy_query {
+        if check_collision(enemy_transform.translation.truncate(), ENEMY_SIZE, player_transform.translation.truncate(), PLAYER_SIZE) {
+            if !shielded {
+                health.health -= 1;
+                sfx.send(PlaySfx(Sfx::PlayerHit));
+            }
+            commands.entity(enemy).despawn();
+        }
+    }
+}
+
+// divers and drifters eventually leave the playfield
+fn clean_up_enemies(mut commands: Commands, query: Query<(Entity, &Transform), With<Enemy>>) {
+    for (enemy, transform) in &query {
+        if PLAYFIELD.is_outside(transform.translation.truncate(), ENEMY_SIZE.y) {
+            commands.entity(enemy).despawn();
+        }
+    }
+}
+
+// formations are removed once every enemy in them is gone
+fn clean_up_formations(mut commands: Commands,
+                       formations: Query<Entity, With<Formation>>,
+                       members: Query<&InFormation>) {
+    let flying: HashSet<Entity> = members.iter().map(|member| member.formation).collect();
+    for formation in &formations {
+        if !flying.contains(&formation) {
+            commands.entity(formation).despawn();
+        }
+    }
+}
+
+// Spawns a boss above the playfield at `x`, it flies down into place
+pub fn spawn_boss(commands: &mut Commands, asset_server: &AssetServer, x: f32) -> Entity {
+    commands.spawn((SpriteBundle {
+        texture: asset_server.load("textures/corro.png"),
+        transform: Transform::from_xyz(x, PLAYFIELD.top() + BOSS_SIZE.y / 2.0, 1.0),
+        sprite: Sprite {
+            custom_size: Some(BOSS_SIZE),
+            color: BOSS_COLOR,
+            ..default()
+        },
+        ..default()
+    }, Boss::new(), OnGameScreen)).with_children(|parent| {
+        // glowing claws, so the player knows where to aim
+        for weak_point in Boss::WEAK_POINTS {
+            parent.spawn(SpriteBundle {
+                transform: Transform::from_translation(weak_point.extend(0.1)),
+                sprite: Sprite {
+                    custom_size: Some(WEAK_POINT_SIZE),
+                    color: Color::rgba(1.0, 0.2, 0.1, 0.6),
+                    ..default()
+                },
+                ..default()
+            });
+        }
+    }).id()
+}
+
+// a boss comes in as the score reaches each of the milestones, unless one is already here
+fn spawn_score_bosses(mut commands: Commands,
+                      asset_server: Res<AssetServer>,
+                      score: Res<Score>,
+                      handle: Res<WaveTableHandle>,
+                      tables: Res<Assets<WaveTable>>,
+                      mut waves: ResMut<Waves>,
+                      boss_query: Query<(), With<Boss>>) {
+    let Some(table) = tables.get(&handle.0) else {
+        return;
+    };
+    let Some(&milestone) = table.boss_scores.get(waves.score_bosses) else {
+        return;
+    };
+    if score.score >= milestone && boss_query.is_empty() && matches!(waves.phase, WavePhase::Spawning { .. }) {
+        waves.score_bosses += 1;
+        spawn_boss(&mut commands, &asset_server, 0.0);
+    }
+}
+
+// flies down into place, then sways from wall to wall
+fn move_boss(mut query: Query<(&mut Transform, &mut Boss)>) {
+    let right_bound = PLAYFIELD.right() - BOSS_SIZE.x / 2.0;
+    for (mut transform, mut boss) in &mut query {
+        if transform.translation.y > BOSS_HEIGHT {
+            transform.translation.y = (transform.translation.y - BOSS_SPEED * TIME_STEP).max(BOSS_HEIGHT);
+            continue;
+        }
+        transform.translation.x += boss.direction * BOSS_SPEED * TIME_STEP;
+        if transform.translation.x.abs() > right_bound {
+            transform.translation.x = transform.translation.x.clamp(-right_bound, right_bound);
+            boss.direction = -boss.direction;
+        }
+    }
+}
+
+// each phase has its own attack
+fn boss_attack(time: Res<Time>,
+               mut commands: Commands,
+               asset_server: Res<AssetServer>,
+               mut rng: ResMut<GameRng>,
+               mut boss_query: Query<(&Transform, &mut Boss)>,
+               enemy_query: Query<(), With<Enemy>>) {
+    for (transform, mut boss) in &mut boss_query {
+        if !boss.attack.tick(time.delta()).just_finished() {
+            continue;
+        }
+        let position = transform.translation - Vec3::new(0.0, BOSS_SIZE.y / 2.0, 0.0);
+        match boss.phase() {
+            BossPhase::Fan => fire_fan(&mut commands, &asset_server, position, 9, 100.0),
+            BossPhase::Homing => {
+                for direction in [Vec2::new(-1.0, -1.0), Vec2::NEG_Y, Vec2::new(1.0, -1.0)] {
+                    let projectile = spawn_enemy_projectile(&mut commands, &asset_server, position, direction.normalize());
+                    commands.entity(projectile).insert(Homing {
+                        turn_rate: 2.0,
+                        time: Timer::from_seconds(3.0, TimerMode::Once),
+                    });
+                }
+            }
+            BossPhase::Summon => {
+                fire_fan(&mut commands, &asset_server, position, 5, 60.0);
+                let minions = enemy_query.iter().count();
+                for side in [-1.0, 1.0].into_iter().take(BOSS_MAX_MINIONS.saturating_sub(minions)) {
+                    let minion = transform.translation.truncate() + Vec2::new(side * BOSS_SIZE.x / 2.0, 0.0);
+                    spawn_enemies(&mut commands, &asset_server, &mut rng, EnemyKind::Diver, Movement::Dive, minion);
+                }
+            }
+        }
+    }
+}
+
+// `shots` shots fanned out over `angle` degrees, centered on straight down
+fn fire_fan(commands: &mut Commands, asset_server: &AssetServer, position: Vec3, shots: u32, angle: f32) {
+    for shot in 0..shots {
+        let offset = if shots > 1 { angle * (shot as f32 / (shots - 1) as f32 - 0.5) } else { 0.0 };
+        let direction = Vec2::from_angle(offset.to_radians()).rotate(Vec2::NEG_Y);
+        spawn_enemy_projectile(commands, asset_server, position, direction);
+    }
+}
+
+fn steer_homing_projectiles(time: Res<Time>,
+                            mut query: Query<(&mut Transform, &mut ProjectileVelocity, &mut Homing), Without<Player>>,
+                            player_query: Query<&Transform, With<Player>>) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    for (mut transform, mut velocity, mut homing) in &mut query {
+        if homing.time.tick(time.delta()).finished() {
+            continue;
+        }
+        let to_player = (player_transform.translation - transform.translation).truncate();
+        let angle = velocity.0.angle_between(to_player);
+        if angle.is_nan() {
+            continue;
+        }
+        let max_turn = homing.turn_rate * TIME_STEP;
+        velocity.0 = Vec2::from_angle(angle.clamp(-max_turn, max_turn)).rotate(velocity.0);
+        transform.rotation = Quat::from_rotation_arc_2d(Vec2::NEG_Y, velocity.0.normalize());
+    }
+}
+
+// player projectiles hurt the boss, more at its weak points. Beating it is worth a lot.
+fn hurt_boss(mut commands: Commands,
+             mut score: ResMut<Score>,
+             mut boss_query: Query<(Entity, &Transform, &mut Boss)>,
+             projectile_query: Query<(Entity, &Transform, Has<Charged>), With<Projectile>>,
+             mut sfx: EventWriter<PlaySfx>) {
+    // runs after `kill_enemy`, so a shot used up on a minion in front of the boss is already gone
+    for (boss_entity, boss_transform, mut boss) in &mut boss_query {
+        let boss_pos = boss_transform.translation.truncate();
+        for (projectile_entity, projectile_transform, charged) in &projectile_query {
+            let projectile_pos = projectile_transform.translation.truncate();
+            let projectile_size = projectile_size(projectile_transform);
+            let damage = if Boss::WEAK_POINTS.iter().any(|&weak_point| check_collision(projectile_pos, projectile_size, boss_pos + weak_point, WEAK_POINT_SIZE)) {
+                WEAK_POINT_DAMAGE
+            } else if check_collision(projectile_pos, projectile_size, boss_pos, BOSS_SIZE) {
+                1
+            } else {
+                continue;
+            };
+            // even piercing shots stop at the boss
+            commands.entity(projectile_entity).despawn();
+            boss.health -= if charged { damage * CHARGED_DAMAGE } else { damage };
+            if boss.health <= 0 {
+                commands.entity(boss_entity).despawn_recursive();
+                score.score += BOSS_SCORE;
+                sfx.send(PlaySfx(Sfx::EnemyDeath));
+                break;
+            }
+        }
+    }
+}
+
+// shows the first boss's health, and goes away when there's no boss
+fn update_boss_bar(mut commands: Commands,
+                   boss_query: Query<&Boss>,
+                   bar_query: Query<Entity, With<BossBar>>,
+                   mut fill_query: Query<(&BossBarFill, &mut Style)>) {
+    let Some(boss) = boss_query.iter().next() else {
+        for bar in &bar_query {
+            commands.entity(bar).despawn_recursive();
+        }
+        return;
+    };
+    if bar_query.is_empty() {
+        spawn_boss_bar(&mut commands);
+    }
+    for (fill, mut style) in &mut fill_query {
+        let segment_health = boss.health - fill.0 as i32 * BOSS_SEGMENT_HEALTH;
+        let full = segment_health.clamp(0, BOSS_SEGMENT_HEALTH) as f32 / BOSS_SEGMENT_HEALTH as f32;
+        style.width = Val::Percent(full * 100.0);
+    }
+}
+
+fn spawn_boss_bar(commands: &mut Commands) {
+    commands.spawn((NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(30.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            column_gap: Val::Px(6.0),
+            ..default()
+        },
+        ..default()
+    }, BossBar, OnGameScreen)).with_children(|parent| {
+        for segment in 0..BOSS_SEGMENTS {
+            parent.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(180.0),
+                    height: Val::Px(18.0),
+                    ..default()
+                },
+                background_color: Color::rgb(0.2, 0.1, 0.1).into(),
+                ..default()
+            }).with_children(|parent| {
+                parent.spawn((NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    background_color: BOSS_COLOR.into(),
+                    ..default()
+                }, BossBarFill(segment)));
+            });
+        }
+    });
+}
+
+const PROJECTILE_SIZE: Vec2 = Vec2::new(0.25*67.0, 0.25*90.0);
+const ENEMY_PROJECTILE_SIZE: Vec2 = Vec2::new(0.25*70.0, 0.25*126.0);
+const PROJECTILE_SPEED: f32 = 250.0;
+const ENEMY_PROJECTILE_SPEED: f32 = 175.0;
+// Enemies reload slower when together they would fire more often than this, so a full screen
+// stays dodgeable
+const MAX_VOLLEYS_PER_SECOND: f32 = 1.5;
+
+fn shoot_projectile(
+    time: Res<Time>,
+    mut weapon: ResMut<Weapon>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    input: Res<ButtonInput<Action>>,
+    query: Query<&Transform, With<Player>>,
+    mut sfx: EventWriter<PlaySfx>,
+) {
+    let player_transform = query.single();
+    let position = player_transform.translation;
+
+    // Check if player is allowed to shoot based on internal timer. With the charged shot the gun
+    // also reloads while Fire is up, so a tap always gets a shot off.
+    if input.pressed(Action::Fire) || weapon.charge {
+        weapon.reload.tick(time.delta());
+    }
+    if weapon.charge {
+        // holding Fire only charges, letting go fires the big shot or, after a tap, a normal one
+        if input.pressed(Action::Fire) {
+            weapon.charging += time.delta_seconds();
+        }
+        if input.just_released(Action::Fire) {
+            if weapon.is_charged() {
+                let projectile = spawn_projectile(&mut commands, &asset_server, position, Vec2::Y);
+                commands.entity(projectile).insert((Charged, Piercing, Transform {
+                    translation: position,
+                    scale: Vec3::splat(CHARGED_SCALE),
+                    ..default()
+                }));
+                sfx.send(PlaySfx(Sfx::Shoot));
+            } else if weapon.reload.finished() {
+                weapon.reload.reset();
+                fire_volley(&mut commands, &asset_server, &weapon, position);
+                sfx.send(PlaySfx(Sfx::Shoot));
+            }
+            weapon.charging = 0.0;
+        }
+    } else if input.pressed(Action::Fire) && weapon.reload.finished() {
+        // Reset the timer
+        weapon.reload.reset();
+        fire_volley(&mut commands, &asset_server, &weapon, position);
+        sfx.send(PlaySfx(Sfx::Shoot));
+    }
+}
+
+// one shot, or more with the shot upgrades
+fn fire_volley(commands: &mut Commands, asset_server: &AssetServer, weapon: &Weapon, position: Vec3) {
+    let shots: &[(f32, f32)] = match weapon.shot {
+        Shot::Single => &[(0.0, 0.0)],
+        Shot::Double => &[(-DOUBLE_SHOT_GAP / 2.0, 0.0), (DOUBLE_SHOT_GAP / 2.0, 0.0)],
+        Shot::Spread => &[(0.0, -SPREAD_ANGLE), (0.0, 0.0), (0.0, SPREAD_ANGLE)],
+    };
+    // side by side, and angled away from straight up
+    for &(offset, angle) in shots {
+        let direction = Vec2::from_angle(angle.to_radians()).rotate(Vec2::Y);
+        let projectile = spawn_projectile(commands, asset_server, position + Vec3::new(offset, 0.0, 0.0), direction);
+        if weapon.piercing {
+            commands.entity(projectile).insert(Piercing);
+        }
+    }
+}
+
+fn spawn_projectile(commands: &mut Commands, asset_server: &AssetServer, position: Vec3, direction: Vec2) -> Entity {
+    commands.spawn((SpriteBundle {
+        texture: asset_server.load("textures/player_projectile.png"),
+        transform: Transform {
+            translation: position,
+            // the sprite points up
+            rotation: Quat::from_rotation_arc_2d(Vec2::Y, direction),
+            ..default()
+        },
+        sprite: Sprite {
+            custom_size: Some(PROJECTILE_SIZE),
+            ..default()
+        },
+        ..default()
+    }, Projectile, ProjectileVelocity(direction * PROJECTILE_SPEED), OnGameScreen)).id()
+}
+
+// charged shots are scaled up
+fn projectile_size(transform: &Transform) -> Vec2 {
+    PROJECTILE_SIZE * transform.scale.truncate()
+}
+// fire the enemies' guns
+fn enemy_projectile(
+    time: Res<Time>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut query: Query<(&Transform, &mut EnemyGun), With<Enemy>>,
+    player_query: Query<(&Transform, &PlayerVelocity), With<Player>>,
+) {
+    let Ok((player_transform, player_velocity)) = player_query.get_single() else {
+        return;
+    };
+    let volleys_per_second: f32 = query.iter().map(|(_, gun)| 1.0 / gun.reload.duration().as_secs_f32()).sum();
+    let reload_speed = (MAX_VOLLEYS_PER_SECOND / volleys_per_second).min(1.0);
+    let player_position = player_transform.translation.truncate();
+
+    for (enemy_transform, mut gun) in &mut query {
+        let position = enemy_transform.translation;
+        let aimed = || lead_target(position.truncate(), player_position, player_velocity.0, ENEMY_PROJECTILE_SPEED);
+        // a burst that has started finishes at its own pace
+        if let Some((shots_left, timer)) = &mut gun.burst {
+            if timer.tick(time.delta()).just_finished() {
+                spawn_enemy_projectile(&mut commands, &asset_server, position, aimed());
+                *shots_left -= 1;
+                if *shots_left == 0 {
+                    gun.burst = None;
+                }
+            }
+            continue;
+        }
+        if !gun.reload.tick(time.delta().mul_f32(reload_speed)).just_finished() {
+            continue;
+        }
+        match gun.pattern {
+            FirePattern::Straight => {
+                spawn_enemy_projectile(&mut commands, &asset_server, position, Vec2::NEG_Y);
+            }
+            FirePattern::Aimed => {
+                spawn_enemy_projectile(&mut commands, &asset_server, position, aimed());
+            }
+            FirePattern::Spread { shots, angle } => fire_fan(&mut commands, &asset_server, position, shots, angle),
+            FirePattern::Burst { shots, interval } => {
+                spawn_enemy_projectile(&mut commands, &asset_server, position, aimed());
+                if shots > 1 {
+                    gun.burst = Some((shots - 1, Timer::from_seconds(interval, TimerMode::Repeating)));
+                }
+            }
+        }
+    }
+}
+
+// Direction to shoot in to hit a target moving at `target_velocity`
+fn lead_target(from: Vec2, target: Vec2, target_velocity: Vec2, speed: f32) -> Vec2 {
+    // where the target will be by the time the shot gets there, refined a couple of times
+    let mut aim = target;
+    for _ in 0..3 {
+        aim = target + target_velocity * (from.distance(aim) / speed);
+    }
+    (aim - from).try_normalize().unwrap_or(Vec2::NEG_Y)
+}
+
+fn spawn_enemy_projectile(commands: &mut Commands, asset_server: &AssetServer, position: Vec3, direction: Vec2) -> Entity {
+    commands.spawn((SpriteBundle {
+        texture: asset_server.load("textures/enemy_projectile.png"),
+        transform: Transform {
+            translation: position,
+            // the sprite points down
+            rotation: Quat::from_rotation_arc_2d(Vec2::NEG_Y, direction),
+            ..default()
+        },
+        sprite: Sprite {
+            custom_size: Some(ENEMY_PROJECTILE_SIZE),
+            ..default()
+        },
+        ..default()
+    }, EnemyProjectile, ProjectileVelocity(direction * ENEMY_PROJECTILE_SPEED), OnGameScreen)).id()
+}
+// move player projectiles
+fn move_projectiles(mut query: Query<(&mut Transform, Option<&ProjectileVelocity>), With<Projectile>>) {
+    for (mut projectile_transform, velocity) in &mut query {
+        let velocity = velocity.map_or(Vec2::new(0.0, PROJECTILE_SPEED), |velocity| velocity.0);
+        projectile_transform.translation += (velocity * TIME_STEP).extend(0.0);
+    }
+}
+
+fn move_enemy_projectiles(mut query: Query<(&mut Transform, Option<&ProjectileVelocity>), With<EnemyProjectile>>) {
+    for (mut projectile_transform, velocity) in &mut query {
+        let velocity = velocity.map_or(Vec2::new(0.0, -ENEMY_PROJECTILE_SPEED), |velocity| velocity.0);
+        projectile_transform.translation += (velocity * TIME_STEP).extend(0.0);
+    }
+}
+// destroy projectiles when they go off screen
+fn destroy_projectiles(
+    mut commands: Commands,
+    query: Query<(Entity, &Transform), With<Projectile>>,
+) {
+    for (projectile_entity, projectile_transform) in &query {
+        if PLAYFIELD.is_outside(projectile_transform.translation.truncate(), PROJECTILE_SIZE.y) {
+            commands.entity(projectile_entity).despawn();
+        }
+    }
+}
+
+fn destroy_enemy_projectiles(
+    mut commands: Commands,
+    query: Query<(Entity, &Transform), With<EnemyProjectile>>,
+) {
+    for (projectile_entity, projectile_transform) in &query {
+        if PLAYFIELD.is_outside(projectile_transform.translation.truncate(), ENEMY_PROJECTILE_SIZE.y) {
+            commands.entity(projectile_entity).despawn();
+        }
+    }
+}
+
+// check for collision between player projectile and enemy
+fn kill_enemy(mut commands: Commands,
+              mut score: ResMut<Score>,
+              mut rng: ResMut<GameRng>,
+              enemy_query: Query<(Entity, &Transform), With<Enemy>>,
+              projectile_query: Query<(Entity, &Transform, Has<Piercing>), With<Projectile>>,
+              mut sfx: EventWriter<PlaySfx>) {
+    // projectiles that already hit something this frame
+    let mut spent = HashSet::new();
+    for (entity, enemy_transform) in enemy_query.iter() {
+        for (projectile_entity, projectile_transform, piercing) in projectile_query.iter(){
+            if spent.contains(&projectile_entity) {
+                continue;
+            }
+            let projectile_pos = Vec2::new(projectile_transform.translation.x, projectile_transform.translation.y);
+            let enemy_pos = Vec2::new(enemy_transform.translation.x, enemy_transform.translation.y);
+            if check_collision(projectile_pos, projectile_size(projectile_transform), enemy_pos, ENEMY_SIZE) {
+                // despawn the enemy, and the projectile unless it pierces
+                commands.entity(entity).despawn();
+                if !piercing {
+                    commands.entity(projectile_entity).despawn();
+                    spent.insert(projectile_entity);
+                }
+                // increase score
+                score.score += 1;
+                sfx.send(PlaySfx(Sfx::EnemyDeath));
+                if rng.gen_bool(PICKUP_DROP_CHANCE) {
+                    let pickup = PickUp::ALL[rng.gen_range(0..PickUp::ALL.len())];
+                    spawn_pickup(&mut commands, pickup, enemy_pos);
+                }
+                // it's dead, the other projectiles miss it
+                break;
+            }
+        }
+    }
+}
+
+pub fn spawn_pickup(commands: &mut Commands, pickup: PickUp, position: Vec2) -> Entity {
+    commands.spawn((SpriteBundle {
+        transform: Transform::from_translation(position.extend(2.0)),
+        sprite: Sprite {
+            custom_size: Some(PICKUP_SIZE),
+            color: pickup.color(),
+            ..default()
+        },
+        ..default()
+    }, pickup, OnGameScreen)).with_children(|parent| {
+        parent.spawn(Text2dBundle {
+            text: Text::from_section(pickup.letter(), TextStyle {
+                font_size: 30.0,
+                color: Color::BLACK,
+                ..default()
+            }),
+            transform: Transform::from_xyz(0.0, 0.0, 0.1),
+            ..default()
+        });
+    }).id()
+}
+
+fn move_pickups(mut commands: Commands, mut query: Query<(Entity, &mut Transform), With<PickUp>>) {
+    for (entity, mut transform) in &mut query {
+        transform.translation.y -= PICKUP_SPEED * TIME_STEP;
+        if PLAYFIELD.is_outside(transform.translation.truncate(), PICKUP_SIZE.y) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn collect_pickups(mut commands: Commands,
+                   mut weapon: ResMut<Weapon>,
+                   player_query: Query<(Entity, &Transform), With<Player>>,
+                   pickup_query: Query<(Entity, &Transform, &PickUp)>,
+                   enemy_projectile_query: Query<Entity, With<EnemyProjectile>>,
+                   mut sfx: EventWriter<PlaySfx>) {
+    let Ok((player, player_transform)) = player_query.get_single() else {
+        return;
+    };
+    for (entity, transform, pickup) in &pickup_query {
+        if !check_collision(transform.translation.truncate(), PICKUP_SIZE, player_transform.translation.truncate(), PLAYER_SIZE) {
+            continue;
+        }
+        commands.entity(entity).despawn_recursive();
+        sfx.send(PlaySfx(Sfx::PowerUp));
+        match pickup {
+            PickUp::DoubleShot => weapon.shot = weapon.shot.max(Shot::Double),
+            PickUp::TripleShot => weapon.shot = Shot::Spread,
+            PickUp::RapidFire => {
+                weapon.rapid_fire = true;
+                weapon.reload.set_duration(Duration::from_secs_f32(RAPID_FIRE_RELOAD_TIME));
+            }
+            PickUp::Piercing => weapon.piercing = true,
+            PickUp::ChargeShot => weapon.charge = true,
+            // catching another one starts the time over
+            PickUp::Shield => {
+                commands.entity(player).insert(PlayerShield(Timer::from_seconds(SHIELD_TIME, TimerMode::Once)));
+            }
+            PickUp::Bomb => {
+                for projectile in &enemy_projectile_query {
+                    commands.entity(projectile).despawn();
+                }
+            }
+        }
+    }
+}
+
+fn wear_off_shield(mut commands: Commands, time: Res<Time>, mut query: Query<(Entity, &mut PlayerShield)>) {
+    for (entity, mut shield) in &mut query {
+        if shield.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<PlayerShield>();
+        }
+    }
+}
+
+// blue while shielded, gold while a charged shot is ready
+fn tint_player(weapon: Res<Weapon>, mut query: Query<(&mut Sprite, Has<PlayerShield>), With<Player>>) {
+    for (mut sprite, shielded) in &mut query {
+        sprite.color = if shielded {
+            Color::rgb(0.5, 0.8, 1.0)
+        } else if weapon.is_charged() {
+            Color::rgb(1.0, 0.9, 0.4)
+        } else {
+            Color::WHITE
+        };
+    }
+}
+
+fn update_weapon_text(weapon: Res<Weapon>, mut query: Query<&mut Text, With<WeaponText>>) {
+    for mut text in &mut query {
+        text.sections[0].value = format!("Weapon: Lv {}", weapon.level());
+    }
+}
+
+// check for collisions between enemy projectile and player
+fn hurt_player(mut commands: Commands,
+               mut health: ResMut<Health>,
+               player_query: Query<(&Transform, Has<PlayerShield>), With<Player>>,
+               enemy_projectile_query: Query<(Entity, &Transform), (With<EnemyProjectile>, Without<Player>)>,
+               mut sfx: EventWriter<PlaySfx>) {
+    for (player_transform, shielded) in player_query.iter() {
+        for (enemy_projectile_entity, enemy_projectile_transform) in enemy_projectile_query.iter(){
+            let enemy_projectile_pos = Vec2::new(enemy_projectile_transform.translation.x, enemy_projectile_transform.translation.y);
+            let player_pos = Vec2::new(player_transform.translation.x, player_transform.translation.y);
+            if check_collision(enemy_projectile_pos, ENEMY_PROJECTILE_SIZE, player_pos, PLAYER_SIZE){
+                // decrease health, unless the shield's up
+                if !shielded {
+                    health.health -= 1;
+                    sfx.send(PlaySfx(Sfx::PlayerHit));
+                }
+                // despawn projectile
+                commands.entity(enemy_projectile_entity).despawn();
+            }
+        }
+    }
+}
+// despawn projectiles that collide with each other
+fn projectile_collision(mut commands: Commands,
+                        projectile_query: Query<(Entity, &Transform, Has<Charged>), With<Projectile>>,
+                        enemy_projectile_query: Query<(Entity, &Transform), (With<EnemyProjectile>, Without<Player>)>) {
+    for (player_projectile_entity, player_projectile_transform, charged) in projectile_query.iter() {
+        for (enemy_projectile_entity, enemy_projectile_transform) in enemy_projectile_query.iter(){
+            let enemy_projectile_pos = Vec2::new(enemy_projectile_transform.translation.x, enemy_projectile_transform.translation.y);
+            let player_projectile_pos = Vec2::new(player_projectile_transform.translation.x, player_projectile_transform.translation.y);
+            if check_collision(enemy_projectile_pos, ENEMY_PROJECTILE_SIZE, player_projectile_pos, projectile_size(player_projectile_transform)){
+                commands.entity(enemy_projectile_entity).despawn();
+                // charged shots plough through
+                if !charged {
+                    commands.entity(player_projectile_entity).despawn();
+                    break;
+                }
+            }
+        }
+    }
+}
+// when player reaches health 0, end the game
+fn kill_player(mut commands: Commands,
+               health: Res<Health>,
+               mut run_ended: EventWriter<RunEnded>,
+               enemy_projectile_query: Query<Entity, (With<EnemyProjectile>, Without<Player>, Without<Projectile>)>,
+               player_projectile_query: Query<Entity, (With<Projectile>, Without<Player>, Without<EnemyProjectile>)>
+) {
+    if health.is_dead() {
+        // despawn enemies
+        for entity in enemy_projectile_query.iter(){
+            commands.entity(entity).despawn();
+        }
+        // despawn projectiles
+        for entity in player_projectile_query.iter(){
+            commands.entity(entity).despawn();
+        }
+        // show the game over screen
+        run_ended.send(RunEnded { won: false });
+    }
+}
@@ -0,0 +1,45 @@
+// Difficulty preset picked in the launcher. Games that get harder as a run goes on start from it
+// and ramp up with their own `Level`.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::replay::Playback;
+
+#[derive(Resource, Component, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        }
+    }
+}
+
+// Difficulty the current run is played at. Changing `Difficulty` only affects the next run, and a
+// replay uses the difficulty it was recorded at.
+#[derive(Resource, Clone, Copy, Default, Debug)]
+pub struct RunDifficulty(pub Difficulty);
+
+pub fn difficulty_plugin(app: &mut App) {
+    app.init_resource::<Difficulty>().init_resource::<RunDifficulty>();
+}
+
+// Added to every game's `OnEnter` by `register_minigame`, like `reseed_rng`
+pub fn pick_run_difficulty(
+    selected: Res<Difficulty>,
+    playback: Option<Res<Playback>>,
+    mut run_difficulty: ResMut<RunDifficulty>,
+) {
+    run_difficulty.0 = playback.map_or(*selected, |playback| playback.replay().difficulty);
+}
@@ -0,0 +1,151 @@
+// Game over screen shared by every game. A game ends its run by sending `RunEnded`; the screen
+// shows the final `Score` and lets the player start a fresh run or go back to the menu.
+
+use bevy::prelude::*;
+
+use crate::high_scores::{check_high_score, spawn_name_prompt, PendingHighScore};
+use crate::hud::Score;
+use crate::replay::Playback;
+use crate::rng::GameRng;
+use crate::ui::{overlay_node, spawn_button, title_text, TEXT_COLOR};
+use crate::{despawn_screen, GameState, GameplaySet};
+
+#[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Hash, States)]
+pub enum RunState {
+    #[default]
+    Playing,
+    GameOver,
+}
+
+// Sent by a game when the run is over, either by winning or by dying
+#[derive(Event, Clone, Copy)]
+pub struct RunEnded {
+    pub won: bool,
+}
+
+// How the last run ended
+#[derive(Resource, Clone, Copy)]
+struct RunOutcome {
+    won: bool,
+}
+
+// Tag component used to tag entities added on the game over screen
+#[derive(Component)]
+struct OnGameOverScreen;
+
+// All actions that can be triggered from a button click
+#[derive(Component)]
+enum GameOverButtonAction {
+    PlayAgain,
+    BackToMenu,
+}
+
+pub fn game_over_plugin(app: &mut App) {
+    app.init_state::<RunState>()
+        .add_event::<RunEnded>()
+        .configure_sets(Update, GameplaySet.run_if(in_state(RunState::Playing)))
+        .configure_sets(FixedUpdate, GameplaySet.run_if(in_state(RunState::Playing)))
+        .add_systems(OnEnter(RunState::GameOver), game_over_setup.after(check_high_score))
+        .add_systems(OnExit(RunState::GameOver), despawn_screen::<OnGameOverScreen>)
+        .add_systems(Update, (end_run, reset_on_game_change))
+        .add_systems(Update, game_over_action.run_if(in_state(RunState::GameOver)));
+}
+
+fn end_run(
+    mut commands: Commands,
+    mut run_ended: EventReader<RunEnded>,
+    run_state: Res<State<RunState>>,
+    mut next_run_state: ResMut<NextState<RunState>>,
+) {
+    if let Some(ended) = run_ended.read().last() {
+        if *run_state.get() == RunState::Playing {
+            commands.insert_resource(RunOutcome { won: ended.won });
+            next_run_state.set(RunState::GameOver);
+        }
+    }
+}
+
+// Every game, including a restarted one, starts out playing
+fn reset_on_game_change(
+    mut transitions: EventReader<StateTransitionEvent<GameState>>,
+    mut next_run_state: ResMut<NextState<RunState>>,
+) {
+    if !transitions.is_empty() {
+        transitions.clear();
+        next_run_state.set(RunState::Playing);
+    }
+}
+
+fn game_over_setup(
+    mut commands: Commands,
+    outcome: Res<RunOutcome>,
+    score: Option<Res<Score>>,
+    pending_high_score: Option<Res<PendingHighScore>>,
+    rng: Res<GameRng>,
+    playback: Option<Res<Playback>>,
+) {
+    let final_score = score.map_or(0, |score| score.score);
+    commands
+        .spawn((overlay_node(), OnGameOverScreen))
+        .with_children(|parent| {
+            parent.spawn(title_text(if outcome.won { "You won!" } else { "Game Over" }));
+            parent.spawn(
+                TextBundle::from_section(
+                    format!("Final score: {final_score}"),
+                    TextStyle {
+                        font_size: 40.0,
+                        color: TEXT_COLOR,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                }),
+            );
+            // lets a bug report say exactly which run went wrong
+            parent.spawn(
+                TextBundle::from_section(
+                    format!("Seed: {}", rng.seed()),
+                    TextStyle {
+                        font_size: 20.0,
+                        color: TEXT_COLOR,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(30.0)),
+                    ..default()
+                }),
+            );
+            if pending_high_score.is_some() {
+                spawn_name_prompt(parent);
+            }
+            // restarting a replay watches it again
+            let play_again = if playback.is_some() { "Watch again" } else { "Play again" };
+            spawn_button(parent, play_again, GameOverButtonAction::PlayAgain);
+            spawn_button(parent, "Back to menu", GameOverButtonAction::BackToMenu);
+        });
+}
+
+fn game_over_action(
+    interaction_query: Query<(&Interaction, &GameOverButtonAction), (Changed<Interaction>, With<Button>)>,
+    game_state: Res<State<GameState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, action) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match action {
+            // going through `Restarting` despawns every entity and re-runs the game's setup,
+            // which inserts fresh resources
+            GameOverButtonAction::PlayAgain => {
+                if let GameState::Playing(id) = *game_state.get() {
+                    next_game_state.set(GameState::Restarting(id));
+                }
+            }
+            GameOverButtonAction::BackToMenu => next_game_state.set(GameState::Menu),
+        }
+    }
+}
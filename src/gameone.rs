@@ -0,0 +1,384 @@
+use bevy::{prelude::*, math::vec3, };
+
+use crate::audio::{PlaySfx, Sfx};
+use crate::controls::Action;
+use crate::game_over::RunEnded;
+use crate::hud::{hud_text, Score, ScoreText};
+use crate::minigame::Minigame;
+use crate::playfield::PLAYFIELD;
+use crate::{despawn_screen, GameState, GameplaySet, OnGameScreen};
+
+// Constant Variables
+
+// Paddle Variables
+const PADDLE_START_Y: f32 = BOTTOM_WALL + 60.0;
+const PADDLE_SIZE: Vec2 = Vec2::new(120.0, 20.0);
+const PADDLE_COLOR: Color = Color::rgb(0.3, 0.3, 0.7);
+const PADDLE_SPEED: f32 = 500.0;
+
+// crab
+const CRAB_STARTING_POSITION: Vec3 = Vec3::new(0.0, -50.0, 1.0);
+const CRAB_SIZE: Vec2 = Vec2::new(30.0, 30.0);
+const CRAB_SPEED: f32 = 400.0;
+const CRAB_INITIAL_DIRECTION: Vec2 = Vec2::new(0.5, -0.5);
+const MAX_CRAB_SPEED: f32 = 600.0;
+
+// Box for the game, inset from the edges of the playfield
+const WALL_MARGIN_X: f32 = 190.0;
+const WALL_MARGIN_Y: f32 = 60.0;
+const LEFT_WALL: f32 = -PLAYFIELD.width / 2.0 + WALL_MARGIN_X;
+const RIGHT_WALL: f32 = PLAYFIELD.width / 2.0 - WALL_MARGIN_X;
+const BOTTOM_WALL: f32 = -PLAYFIELD.height / 2.0 + WALL_MARGIN_Y;
+const TOP_WALL: f32 = PLAYFIELD.height / 2.0 - WALL_MARGIN_Y;
+const WALL_THICKNESS: f32 = 10.0;
+const WALL_BLOCK_WIDTH: f32 = RIGHT_WALL - LEFT_WALL;
+const WALL_BLOCK_HEIGHT: f32 = TOP_WALL - BOTTOM_WALL;
+const WALL_COLOR: Color = Color::rgb(0.8, 0.8, 0.8);
+
+// scoreboard
+const SCOREBOARD_FONT_SIZE:f32 = 40.0;
+const SCOREBOARD_TEXT_PADDING: Val = Val::Px(5.0);
+const TEXT_COLOR: Color = Color::rgb(0.5, 0.5, 1.0);
+
+// win screen
+const WIN_SCREEN_POSITION: Vec3 = Vec3::new(0.0, 0.0, 1.0);
+const WIN_SCREEN_SIZE: Vec2 = Vec2::new(250.0, 250.0);
+
+
+
+pub const STATE: GameState = GameState::Playing("gameone");
+
+pub struct CatchTheCrab;
+
+impl Minigame for CatchTheCrab {
+    fn name(&self) -> &'static str {
+        "Catch the Crab"
+    }
+
+    fn description(&self) -> &'static str {
+        "Bounce the crab off your paddle 10 times"
+    }
+
+    fn icon(&self) -> &'static str {
+        "textures/rustacean-flat-happy.png"
+    }
+
+    fn state(&self) -> GameState {
+        STATE
+    }
+
+    fn controls(&self) -> &'static [(Action, KeyCode)] {
+        &[(Action::MoveLeft, KeyCode::ArrowLeft), (Action::MoveRight, KeyCode::ArrowRight)]
+    }
+
+    fn music(&self) -> Option<&'static str> {
+        Some("audio/gameone_music.wav")
+    }
+
+    fn build(&self, app: &mut App) {
+        gameone_plugin(app);
+    }
+}
+
+// GAME ONE PLUGIN
+fn gameone_plugin(app: &mut App) {
+    app.add_systems(OnEnter(STATE), setup) //these systems are really just functions
+        .add_systems(Update, check_win.run_if(in_state(STATE)).in_set(GameplaySet))
+        .add_systems(FixedUpdate,
+                     (move_paddle,
+                      apply_velocity,
+                      check_crab_paddle_collisions.after(apply_velocity),
+                      check_crab_collisions.after(apply_velocity),)
+                         .run_if(in_state(STATE)).in_set(GameplaySet))// runs at a fixed rate
+        .add_systems(OnExit(STATE), despawn_screen::<OnGameScreen>);
+}
+
+// The class for the paddle object
+#[derive(Component)]
+pub struct Paddle;
+
+#[derive(Component)]
+pub struct Crab;
+
+#[derive(Component, Deref, DerefMut)]
+pub struct Velocity(pub Vec2);
+
+#[derive(Component)]
+struct Collider;
+
+#[derive(Bundle)]
+struct WallBundle{
+    sprite_bundle: SpriteBundle,
+    collider: Collider,
+}
+
+#[derive(Component)]
+struct GameOverText;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>){
+    // every run starts from a fresh score
+    commands.insert_resource(Score::default());
+
+    // spawn the paddle
+    commands.spawn(
+        (SpriteBundle{
+            transform: Transform{
+                translation: vec3(0., PADDLE_START_Y, 0.),
+                ..default()
+            },
+            sprite: Sprite {
+                color: PADDLE_COLOR,
+                custom_size: Some(PADDLE_SIZE),
+                ..default()
+            },
+            ..default()
+        },
+        Paddle,
+        OnGameScreen,) // Add paddle component to the player
+        );
+
+    // spawn the crab
+    let crab_texture = asset_server.load("textures/rustacean-flat-happy.png");
+    commands.spawn(
+        (SpriteBundle{
+            transform: Transform{
+                translation: CRAB_STARTING_POSITION,
+                ..default()
+            },
+            sprite: Sprite {
+                //color: CRAB_COLOR,
+                custom_size: Some(CRAB_SIZE),
+                ..default()
+            },
+            texture: crab_texture,
+            ..default()
+        },
+         Crab,
+        Velocity(CRAB_SPEED * CRAB_INITIAL_DIRECTION),
+        OnGameScreen,
+        ) // Add paddle component to the player
+    );
+
+    // spawn box from walls
+    {
+        let vertical_wall_size: Vec2 = Vec2::new(WALL_THICKNESS, WALL_BLOCK_HEIGHT + WALL_THICKNESS);
+        let horizantal_wall_size: Vec2 = Vec2::new(WALL_BLOCK_WIDTH + WALL_THICKNESS, WALL_THICKNESS);
+
+        // left wall
+        commands.spawn((WallBundle {
+            sprite_bundle: SpriteBundle {
+                transform: Transform {
+                    translation: vec3(LEFT_WALL, 0.0, 0.0),
+                    ..default()
+                },
+                sprite: Sprite {
+                    color: WALL_COLOR,
+                    custom_size: Some(vertical_wall_size),
+                    ..default()
+                },
+                ..default()
+            },
+            collider: Collider
+        }, OnGameScreen));
+
+        // right wall
+        commands.spawn((WallBundle {
+            sprite_bundle: SpriteBundle {
+                transform: Transform {
+                    translation: vec3(RIGHT_WALL, 0.0, 0.0),
+                    ..default()
+                },
+                sprite: Sprite {
+                    color: WALL_COLOR,
+                    custom_size: Some(vertical_wall_size),
+                    ..default()
+                },
+                ..default()
+            },
+            collider: Collider
+        }, OnGameScreen));
+
+        // bottom wall
+        commands.spawn((WallBundle {
+            sprite_bundle: SpriteBundle {
+                transform: Transform {
+                    translation: vec3(0.0, BOTTOM_WALL, 0.0),
+                    ..default()
+                },
+                sprite: Sprite {
+                    color: WALL_COLOR,
+                    custom_size: Some(horizantal_wall_size),
+                    ..default()
+                },
+                ..default()
+            },
+            collider: Collider
+        }, OnGameScreen));
+
+        //top wall
+        commands.spawn((WallBundle {
+            sprite_bundle: SpriteBundle {
+                transform: Transform {
+                    translation: vec3(0.0, TOP_WALL, 0.0),
+                    ..default()
+                },
+                sprite: Sprite {
+                    color: WALL_COLOR,
+                    custom_size: Some(horizantal_wall_size),
+                    ..default()
+                },
+                ..default()
+            },
+            collider: Collider
+        }, OnGameScreen));
+    }
+
+        // scoreboard
+        commands.spawn((hud_text(
+            "Score: ",
+            TextStyle{
+                font_size: SCOREBOARD_FONT_SIZE,
+                color: TEXT_COLOR,
+                ..default()
+            },
+        ).with_style(
+            Style{
+                position_type: PositionType::Absolute,
+                top: SCOREBOARD_TEXT_PADDING,
+                left: SCOREBOARD_TEXT_PADDING,
+                ..default()
+
+            }
+        ), ScoreText, OnGameScreen,));
+
+}
+
+fn move_paddle(
+    input: Res<ButtonInput<Action>>,
+    time_step: Res<Time>,
+    mut query: Query<&mut Transform, With<Paddle>>, // gives reference to the paddle
+){
+    let mut paddle_transform = query.single_mut(); // makes the paddle a singleton
+    let mut direction = 0.0;
+
+    if input.pressed(Action::MoveLeft){
+        direction -= 1.0;
+    }
+    if input.pressed(Action::MoveRight){
+        direction += 1.0;
+    }
+
+    let mut new_x: f32 = paddle_transform.translation.x + direction * PADDLE_SPEED * time_step.delta_seconds();
+
+    // "Collision"
+    new_x = new_x.min(RIGHT_WALL - (WALL_THICKNESS+PADDLE_SIZE.x) * 0.5); // take either the new x position or the wall
+    new_x = new_x.max(LEFT_WALL + (WALL_THICKNESS+PADDLE_SIZE.x) * 0.5); // take either the new x position or the wall position
+
+    paddle_transform.translation.x = new_x;
+}
+
+fn apply_velocity(mut query: Query<(&mut Transform, &Velocity)>, time_step: Res<Time>){
+    let dt = time_step.delta_seconds();
+    for(mut transform, velocity) in &mut query{
+        transform.translation.x += velocity.x * dt;
+        transform.translation.y += velocity.y * dt;
+    }
+
+}
+
+fn check_crab_collisions(
+    mut crab_query: Query<(&mut Velocity, &Transform), With<Crab>>
+){
+    let x_min: f32 = LEFT_WALL + (WALL_THICKNESS+CRAB_SIZE.x) * 0.5;
+    let x_max: f32 = RIGHT_WALL - (WALL_THICKNESS+CRAB_SIZE.x) * 0.5;
+    let y_min: f32 = BOTTOM_WALL + (WALL_THICKNESS+CRAB_SIZE.y) * 0.5;
+    let y_max: f32 = TOP_WALL - (WALL_THICKNESS+CRAB_SIZE.y) * 0.5;
+
+    for (mut crab_velocity, crab_transform) in &mut crab_query {
+        let translation: Vec3 = crab_transform.translation;
+
+        if translation.x < x_min || translation.x > x_max {
+            crab_velocity.x *= -1.
+        }
+
+        if translation.y < y_min || translation.y > y_max {
+            crab_velocity.y *= -1.;
+        }
+    }
+}
+
+fn check_crab_paddle_collisions(
+    mut crab_query: Query<(&mut Velocity, &Transform), With<Crab>>,
+    mut score: ResMut<Score>,
+    paddle_query: Query<&Transform, With<Paddle>>,
+    mut sfx: EventWriter<PlaySfx>,
+){
+    for (mut crab_velocity, crab_transform) in &mut crab_query {
+        for transform in &paddle_query {
+            let x_min: f32 = transform.translation.x - (PADDLE_SIZE.x/2.0);
+            let x_max: f32 = transform.translation.x + (PADDLE_SIZE.x/2.0);
+            let y_min: f32 = transform.translation.y - (PADDLE_SIZE.y + CRAB_SIZE.y) * 0.5;
+            let y_max: f32 = transform.translation.y + (PADDLE_SIZE.y + CRAB_SIZE.y) * 0.5;
+
+            let translation: Vec3 = crab_transform.translation;
+
+            if translation.x >= x_min && translation.x <= x_max
+                && translation.y >= y_min && translation.y <= y_max {
+                //increase speed by 15%
+                if crab_velocity.y.abs() < MAX_CRAB_SPEED {
+                    crab_velocity.y *= -1.15;
+                }else{
+                    crab_velocity.y *= -1.;
+
+                }
+                score.score += 1;
+                sfx.send(PlaySfx(Sfx::Bounce));
+            }
+
+        }
+
+    }
+}
+
+fn check_win(score: Res<Score>, mut crab_query: Query<&mut Velocity>, mut commands: Commands, asset_server: Res<AssetServer>,
+             mut run_ended: EventWriter<RunEnded>)
+{
+
+    if score.score >= 10 {
+        run_ended.send(RunEnded { won: true });
+        for mut crab_velocity in &mut crab_query {
+            crab_velocity.x = 0.;
+            crab_velocity.y = 0.;
+
+            // win screen spawn
+            let win_texture = asset_server.load("textures/wingraphic.png");
+            commands.spawn(
+                (SpriteBundle{
+                    transform: Transform{
+                        translation: WIN_SCREEN_POSITION,
+                        ..default()
+                    },
+                    sprite: Sprite {
+                        //color: CRAB_COLOR,
+                        custom_size: Some(WIN_SCREEN_SIZE),
+                        ..default()
+                    },
+                    texture: win_texture,
+                    ..default()
+                },
+                 GameOverText,
+                 OnGameScreen,
+                )
+            );
+
+
+        }
+
+
+    }
+
+}
+
+
+
+
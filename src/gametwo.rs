@@ -0,0 +1,840 @@
+use bevy::prelude::*;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::utils::BoxedFuture;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use serde::Deserialize;
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::audio::{PlaySfx, Sfx};
+use crate::collision::check_collision;
+use crate::controls::Action;
+use crate::difficulty::{pick_run_difficulty, Difficulty, RunDifficulty};
+use crate::game_over::RunEnded;
+use crate::hud::{hud_text, Health, HealthText, Level, LevelText, Score, ScoreText};
+use crate::player::Player;
+use crate::playfield::PLAYFIELD;
+use crate::minigame::Minigame;
+use crate::rng::GameRng;
+use crate::{despawn_screen, GameState, GameplaySet, OnGameScreen};
+
+
+const PLAYER_SIZE: Vec2 = Vec2::new(0.5*460.0, 0.5*246.0);
+
+const PLAYER_SPEED: f32 = 550.0; 
+
+const START_HEALTH: i32 = 3;
+
+const ITEMS_FILE: &str = "gametwo/items.ron";
+
+// Seconds each level lasts, the game stops getting harder after the last one
+const LEVEL_TIME: f32 = 20.0;
+const MAX_LEVEL: u32 = 10;
+
+// How hard a run is, at level 1 for each preset
+#[derive(Clone, Copy, Debug)]
+struct DifficultyCurve {
+    // multiplies every item's fall speed
+    fall_speed: f32,
+    // seconds between items
+    spawn_interval: f32,
+    // multiplies the spawn weight of junk
+    harmful_weight: f32,
+}
+
+impl DifficultyCurve {
+    fn for_difficulty(difficulty: Difficulty) -> Self {
+        match difficulty {
+            Difficulty::Easy => DifficultyCurve { fall_speed: 0.8, spawn_interval: 0.65, harmful_weight: 0.7 },
+            Difficulty::Normal => DifficultyCurve { fall_speed: 1.0, spawn_interval: 0.5, harmful_weight: 1.0 },
+            Difficulty::Hard => DifficultyCurve { fall_speed: 1.25, spawn_interval: 0.4, harmful_weight: 1.4 },
+        }
+    }
+
+    // Every level makes items fall faster, come more often and more likely be junk
+    fn at_level(self, level: u32) -> Self {
+        let ramp = (level.clamp(1, MAX_LEVEL) - 1) as f32;
+        DifficultyCurve {
+            fall_speed: self.fall_speed * (1.0 + 0.08 * ramp),
+            spawn_interval: self.spawn_interval / (1.0 + 0.1 * ramp),
+            harmful_weight: self.harmful_weight * (1.0 + 0.15 * ramp),
+        }
+    }
+}
+
+// Drops the next item and keeps up with the current level
+#[derive(Resource)]
+struct Spawner {
+    timer: Timer,
+    curve: DifficultyCurve,
+}
+
+#[derive(Resource, Deref, DerefMut)]
+struct LevelTimer(Timer);
+
+// Food caught in a row raises the multiplier by one every `COMBO_STEP` catches, up to `MAX_MULTIPLIER`
+const COMBO_STEP: u32 = 3;
+const MAX_MULTIPLIER: i32 = 5;
+// Seconds a "+N" popup stays up, and how far it rises meanwhile
+const POPUP_TIME: f32 = 0.8;
+const POPUP_RISE: f32 = 60.0;
+
+// Food caught since the last junk or missed food
+#[derive(Resource, Default, Debug)]
+pub struct Combo {
+    pub streak: u32,
+}
+
+impl Combo {
+    pub fn multiplier(&self) -> i32 {
+        (1 + (self.streak / COMBO_STEP) as i32).min(MAX_MULTIPLIER)
+    }
+}
+
+// text for combo display
+#[derive(Component)]
+struct ComboText;
+
+// "+N" floating up from where an item was caught
+#[derive(Component)]
+struct ScorePopup {
+    timer: Timer,
+}
+
+// One kind of falling item, as listed in `assets/gametwo/items.ron`
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct ItemDef {
+    pub name: String,
+    pub texture: String,
+    // drawn size
+    pub size: Vec2,
+    // collision size, the drawn size if left out
+    #[serde(default)]
+    pub hitbox: Option<Vec2>,
+    pub fall_speed: f32,
+    #[serde(default)]
+    pub score: i32,
+    #[serde(default)]
+    pub damage: i32,
+    #[serde(default = "default_spawn_weight")]
+    pub spawn_weight: f32,
+    #[serde(default)]
+    pub effect: Option<ItemEffect>,
+    // loaded along with the item list
+    #[serde(skip)]
+    pub image: Handle<Image>,
+}
+
+impl ItemDef {
+    // Anything that hurts is junk, the rest is food or a power-up
+    pub fn is_good(&self) -> bool {
+        self.damage <= 0
+    }
+
+    // Harmless and worth points, only food counts towards the combo
+    pub fn is_food(&self) -> bool {
+        self.is_good() && self.score > 0
+    }
+
+    pub fn hitbox(&self) -> Vec2 {
+        self.hitbox.unwrap_or(self.size)
+    }
+}
+
+fn default_spawn_weight() -> f32 {
+    1.0
+}
+
+// Extra things that happen when an item is caught
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum ItemEffect {
+    // gives back health, up to what a run starts with
+    Heal(i32),
+    // lasts this many seconds, catching another one starts the time over
+    PowerUp(PowerUp, f32),
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum PowerUp {
+    // the next piece of junk does nothing
+    Shield,
+    // nearby food drifts towards Ferris
+    Magnet,
+    // everything falls at half speed
+    SlowMotion,
+    DoublePoints,
+}
+
+impl PowerUp {
+    pub const ALL: [PowerUp; 4] = [PowerUp::Shield, PowerUp::Magnet, PowerUp::SlowMotion, PowerUp::DoublePoints];
+
+    // Shown in the HUD while it's active
+    fn icon(self) -> &'static str {
+        match self {
+            PowerUp::Shield => "gametwo/shell.png",
+            PowerUp::Magnet => "gametwo/magnet.png",
+            PowerUp::SlowMotion => "gametwo/hourglass.png",
+            PowerUp::DoublePoints => "gametwo/star.png",
+        }
+    }
+}
+
+// How far away the magnet reaches food, and how fast it pulls it sideways
+const MAGNET_RADIUS: f32 = 350.0;
+const MAGNET_SPEED: f32 = 400.0;
+const SLOW_MOTION_FACTOR: f32 = 0.5;
+
+// Power-ups running right now and the time they have left
+#[derive(Resource, Default)]
+pub struct ActivePowerUps {
+    timers: BTreeMap<PowerUp, Timer>,
+}
+
+impl ActivePowerUps {
+    pub fn is_active(&self, power_up: PowerUp) -> bool {
+        self.timers.contains_key(&power_up)
+    }
+
+    // Seconds left
+    pub fn remaining(&self, power_up: PowerUp) -> Option<f32> {
+        self.timers.get(&power_up).map(|timer| timer.remaining_secs())
+    }
+
+    fn grant(&mut self, power_up: PowerUp, seconds: f32) {
+        self.timers.insert(power_up, Timer::from_seconds(seconds, TimerMode::Once));
+    }
+}
+
+// A power-up's slot in the HUD, only shown while it's active
+#[derive(Component)]
+struct PowerUpIcon(PowerUp);
+
+#[derive(Component)]
+struct PowerUpCountdown(PowerUp);
+
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct ItemTable {
+    pub items: Vec<ItemDef>,
+}
+
+#[derive(Resource)]
+struct ItemTableHandle(Handle<ItemTable>);
+
+#[derive(Default)]
+struct ItemTableLoader;
+
+impl AssetLoader for ItemTableLoader {
+    type Asset = ItemTable;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<ItemTable, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            let mut table: ItemTable = ron::de::from_bytes(&bytes)?;
+            for item in &mut table.items {
+                item.image = load_context.load(item.texture.clone());
+            }
+            Ok(table)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["items.ron"]
+    }
+}
+
+pub const STATE: GameState = GameState::Playing("gametwo");
+
+pub struct SeafoodScramble;
+
+impl Minigame for SeafoodScramble {
+    fn name(&self) -> &'static str {
+        "Seafood Scramble"
+    }
+
+    fn description(&self) -> &'static str {
+        "Catch the food, dodge the junk"
+    }
+
+    fn icon(&self) -> &'static str {
+        "gametwo/food1.png"
+    }
+
+    fn state(&self) -> GameState {
+        STATE
+    }
+
+    fn controls(&self) -> &'static [(Action, KeyCode)] {
+        &[(Action::MoveLeft, KeyCode::KeyA), (Action::MoveRight, KeyCode::KeyD)]
+    }
+
+    fn music(&self) -> Option<&'static str> {
+        Some("audio/gametwo_music.wav")
+    }
+
+    fn is_loaded(&self, world: &World) -> bool {
+        world
+            .get_resource::<ItemTableHandle>()
+            .is_some_and(|handle| world.resource::<Assets<ItemTable>>().contains(&handle.0))
+    }
+
+    fn build(&self, app: &mut App) {
+        gametwo_plugin(app);
+    }
+}
+
+// GAME TWO PLUGIN
+fn gametwo_plugin(app: &mut App) {
+    app.init_asset::<ItemTable>()
+        .register_asset_loader(ItemTableLoader)
+        // loaded up front so it's ready by the time a run starts
+        .add_systems(Startup, load_item_table)
+        .add_event::<ItemCaught>()
+        .init_resource::<Combo>()
+        .init_resource::<ActivePowerUps>()
+        .add_systems(OnEnter(STATE), setup.after(pick_run_difficulty))
+        .add_systems(
+            Update,
+            (
+                (raise_level, spawn_item).chain(),
+                (move_player, animate_player).chain(),
+                // items move and are caught after the player has moved
+                (
+                    tick_power_ups,
+                    fall_items,
+                    pull_food,
+                    pick_up_items,
+                    apply_item_effects,
+                    clean_up_items,
+                    end_run_on_death,
+                ).chain().after(move_player),
+                (spawn_score_popups.after(pick_up_items), float_score_popups),
+                play_catch_sounds.after(pick_up_items),
+                (update_combo_text, update_power_up_row).after(clean_up_items),
+            ).run_if(in_state(STATE)).in_set(GameplaySet)
+        )
+        //.add_systems(Update, update_projectiles)
+        .add_systems(OnExit(STATE), despawn_screen::<OnGameScreen>);
+}
+
+#[derive(Component)]
+struct AnimationIndices {
+    first: usize,
+    last: usize,
+}
+
+#[derive(Component, Deref, DerefMut)]
+struct AnimationTimer(Timer);
+
+fn load_item_table(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(ItemTableHandle(asset_server.load(ITEMS_FILE)));
+}
+
+// Goes up a level every `LEVEL_TIME` seconds of play
+fn raise_level(
+    time: Res<Time>,
+    difficulty: Res<RunDifficulty>,
+    mut level: ResMut<Level>,
+    mut level_timer: ResMut<LevelTimer>,
+    mut spawner: ResMut<Spawner>,
+) {
+    if level_timer.tick(time.delta()).just_finished() && level.level < MAX_LEVEL {
+        level.level += 1;
+        let curve = DifficultyCurve::for_difficulty(difficulty.0).at_level(level.level);
+        spawner.curve = curve;
+        spawner.timer.set_duration(Duration::from_secs_f32(curve.spawn_interval));
+    }
+}
+
+fn spawn_item(mut commands: Commands, time: Res<Time>, handle: Res<ItemTableHandle>, tables: Res<Assets<ItemTable>>,
+    mut spawner: ResMut<Spawner>, mut rng: ResMut<GameRng>) {
+    if !spawner.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let Some(table) = tables.get(&handle.0) else {
+        return;
+    };
+    let curve = spawner.curve;
+    let weights = table.items.iter().map(|item| {
+        let weight = item.spawn_weight.max(0.0);
+        if item.is_good() { weight } else { weight * curve.harmful_weight }
+    });
+    // nothing to pick from if every weight is zero
+    let Ok(weights) = WeightedIndex::new(weights) else {
+        return;
+    };
+    let mut item = table.items[weights.sample(&mut **rng)].clone();
+    item.fall_speed *= curve.fall_speed;
+
+    let size = item.size;
+
+    let x = rng.gen_range(PLAYFIELD.left() + size.x/2.0..PLAYFIELD.right() - size.x/2.0);
+    let y = PLAYFIELD.top() + size.y/2.0;
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: Transform::from_xyz(x, y, 2.0),
+            //transform: Transform::from_scale(Vec3::splat(0.5)),
+            texture: item.image.clone_weak(),
+            ..default()
+        },
+        FallingItem(item),
+        OnGameScreen,
+    ));
+}
+
+
+// Sent whenever the player catches a falling item, good or bad
+#[derive(Event, Clone, Debug)]
+pub struct ItemCaught {
+    pub good: bool,
+    // `ItemDef::name`
+    pub kind: String,
+    // score gained, with the combo multiplier applied
+    pub points: i32,
+    // health lost, nothing if a shield took the hit
+    pub damage: i32,
+    pub effect: Option<ItemEffect>,
+    pub position: Vec2,
+}
+
+fn move_player(
+    time: Res<Time>,
+    input: Res<ButtonInput<Action>>,
+    mut query: Query<(&mut Transform, &mut Walking), With<Player>>,
+) {
+    let (mut transform, mut walking) = query.single_mut();
+
+    let left_bound = PLAYFIELD.left() + PLAYER_SIZE.x/2.0;
+    let right_bound = -left_bound;
+
+    let old_x = transform.translation.x;
+    if input.pressed(Action::MoveRight) {
+        transform.translation.x += PLAYER_SPEED * time.delta_seconds();
+    }
+    if input.pressed(Action::MoveLeft) {
+        transform.translation.x -= PLAYER_SPEED * time.delta_seconds();
+    }
+    transform.translation.x = f32::max(left_bound, f32::min(right_bound, transform.translation.x)); // lock it inside the bounds.
+
+    walking.0 = old_x != transform.translation.x;
+}
+
+// Ferris walks while moving and stands still otherwise
+fn animate_player(
+    time: Res<Time>,
+    mut query: Query<(&Walking, &AnimationIndices, &mut AnimationTimer, &mut TextureAtlas), With<Player>>,
+) {
+    let (walking, indices, mut timer, mut atlas) = query.single_mut();
+    if !walking.0 {
+        atlas.index = indices.first;
+        return;
+    }
+    timer.tick(time.delta());
+    if timer.just_finished() {
+        atlas.index = if atlas.index == indices.last {
+            indices.first + 1
+        } else {
+            atlas.index + 1
+        };
+    }
+}
+
+fn tick_power_ups(time: Res<Time>, mut power_ups: ResMut<ActivePowerUps>) {
+    power_ups.timers.retain(|_, timer| !timer.tick(time.delta()).finished());
+}
+
+fn fall_items(time: Res<Time>, power_ups: Res<ActivePowerUps>, mut query: Query<(&mut Transform, &FallingItem)>) {
+    let slow_down = if power_ups.is_active(PowerUp::SlowMotion) { SLOW_MOTION_FACTOR } else { 1.0 };
+    for (mut transform, item) in &mut query {
+        transform.translation.y -= item.fall_speed * slow_down * time.delta_seconds();
+    }
+}
+
+// With the magnet on, food close to Ferris drifts sideways until it's right above
+fn pull_food(
+    time: Res<Time>,
+    power_ups: Res<ActivePowerUps>,
+    player_query: Query<&Transform, With<Player>>,
+    mut item_query: Query<(&mut Transform, &FallingItem), Without<Player>>,
+) {
+    if !power_ups.is_active(PowerUp::Magnet) {
+        return;
+    }
+    let player_pos = player_query.single().translation;
+    for (mut transform, item) in &mut item_query {
+        if !item.is_food() || transform.translation.distance(player_pos) > MAGNET_RADIUS {
+            continue;
+        }
+        let dx = player_pos.x - transform.translation.x;
+        transform.translation.x += dx.signum() * (MAGNET_SPEED * time.delta_seconds()).min(dx.abs());
+    }
+}
+
+// Food scores with the combo multiplier and keeps the streak going, junk costs health and ends it
+// unless a shield takes the hit
+fn pick_up_items(
+    mut commands: Commands,
+    mut score: ResMut<Score>,
+    mut combo: ResMut<Combo>,
+    mut power_ups: ResMut<ActivePowerUps>,
+    mut item_caught: EventWriter<ItemCaught>,
+    player_query: Query<&Transform, With<Player>>,
+    item_query: Query<(Entity, &Transform, &FallingItem)>,
+) {
+    let player_pos = player_query.single().translation.truncate();
+    for (entity, transform, item) in &item_query {
+        if !check_collision(player_pos, PLAYER_SIZE, transform.translation.truncate(), item.hitbox()) {
+            continue;
+        }
+        let mut points = item.score * combo.multiplier();
+        if power_ups.is_active(PowerUp::DoublePoints) {
+            points *= 2;
+        }
+        score.score += points;
+        let mut damage = item.damage;
+        if item.is_food() {
+            combo.streak += 1;
+        } else if !item.is_good() {
+            if power_ups.timers.remove(&PowerUp::Shield).is_some() {
+                damage = 0;
+            } else {
+                combo.streak = 0;
+            }
+        }
+        item_caught.send(ItemCaught {
+            good: item.is_good(),
+            kind: item.name.clone(),
+            points,
+            damage,
+            effect: item.effect,
+            position: transform.translation.truncate(),
+        });
+        commands.entity(entity).despawn();
+    }
+}
+
+fn play_catch_sounds(mut item_caught: EventReader<ItemCaught>, mut sfx: EventWriter<PlaySfx>) {
+    for caught in item_caught.read() {
+        let sound = match caught.effect {
+            _ if !caught.good => Sfx::Hazard,
+            Some(ItemEffect::PowerUp(..)) => Sfx::PowerUp,
+            _ => Sfx::Catch,
+        };
+        sfx.send(PlaySfx(sound));
+    }
+}
+
+fn apply_item_effects(
+    mut item_caught: EventReader<ItemCaught>,
+    mut health: ResMut<Health>,
+    mut power_ups: ResMut<ActivePowerUps>,
+) {
+    for caught in item_caught.read() {
+        health.health -= caught.damage;
+        match caught.effect {
+            Some(ItemEffect::Heal(amount)) if health.health < START_HEALTH => {
+                health.health = (health.health + amount).min(START_HEALTH);
+            }
+            Some(ItemEffect::PowerUp(power_up, seconds)) => power_ups.grant(power_up, seconds),
+            _ => {}
+        }
+    }
+}
+
+// Despawns items once their top is below the bottom of the screen, letting food drop ends the combo
+fn clean_up_items(
+    mut commands: Commands,
+    mut combo: ResMut<Combo>,
+    query: Query<(Entity, &Transform, &FallingItem)>,
+) {
+    for (entity, transform, item) in &query {
+        if transform.translation.y + item.size.y/2.0 <= PLAYFIELD.bottom() {
+            if item.is_food() {
+                combo.streak = 0;
+            }
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn spawn_score_popups(mut commands: Commands, mut item_caught: EventReader<ItemCaught>) {
+    for caught in item_caught.read() {
+        if caught.points <= 0 {
+            continue;
+        }
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    format!("+{}", caught.points),
+                    TextStyle {
+                        font_size: 36.0,
+                        color: Color::GOLD,
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_translation(caught.position.extend(3.0)),
+                ..default()
+            },
+            ScorePopup { timer: Timer::from_seconds(POPUP_TIME, TimerMode::Once) },
+            OnGameScreen,
+        ));
+    }
+}
+
+// Popups drift up and fade out
+fn float_score_popups(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut ScorePopup, &mut Transform, &mut Text)>,
+) {
+    for (entity, mut popup, mut transform, mut text) in &mut query {
+        popup.timer.tick(time.delta());
+        if popup.timer.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation.y += POPUP_RISE / POPUP_TIME * time.delta_seconds();
+        let alpha = 1.0 - popup.timer.fraction();
+        for section in &mut text.sections {
+            section.style.color.set_a(alpha);
+        }
+    }
+}
+
+fn update_combo_text(combo: Res<Combo>, mut query: Query<&mut Text, With<ComboText>>) {
+    for mut text in &mut query {
+        text.sections[1].value = format!("x{}", combo.multiplier());
+    }
+}
+
+// Shows the icon of every active power-up with the seconds it has left
+fn update_power_up_row(
+    power_ups: Res<ActivePowerUps>,
+    mut icons: Query<(&PowerUpIcon, &mut Style)>,
+    mut countdowns: Query<(&PowerUpCountdown, &mut Text)>,
+) {
+    for (icon, mut style) in &mut icons {
+        let display = if power_ups.is_active(icon.0) { Display::Flex } else { Display::None };
+        if style.display != display {
+            style.display = display;
+        }
+    }
+    for (countdown, mut text) in &mut countdowns {
+        if let Some(remaining) = power_ups.remaining(countdown.0) {
+            text.sections[0].value = format!("{}", remaining.ceil() as u32);
+        }
+    }
+}
+
+fn end_run_on_death(health: Res<Health>, mut run_ended: EventWriter<RunEnded>) {
+    if health.is_dead() {
+        run_ended.send(RunEnded { won: false });
+    }
+}
+
+// A falling item and everything about its kind
+#[derive(Component, Deref)]
+pub struct FallingItem(pub ItemDef);
+
+// Whether the player moved this frame, drives the walk animation
+#[derive(Component, Default)]
+struct Walking(bool);
+
+fn setup( mut commands: Commands, asset_server: Res<AssetServer>, mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    difficulty: Res<RunDifficulty> ) {
+    // every run starts from full health, no score and the first level
+    commands.insert_resource(Health { health: START_HEALTH });
+    commands.insert_resource(Score::default());
+    commands.insert_resource(Level::default());
+    commands.insert_resource(Combo::default());
+    commands.insert_resource(ActivePowerUps::default());
+    commands.insert_resource(LevelTimer(Timer::from_seconds(LEVEL_TIME, TimerMode::Repeating)));
+    let curve = DifficultyCurve::for_difficulty(difficulty.0);
+    commands.insert_resource(Spawner {
+        timer: Timer::from_seconds(curve.spawn_interval, TimerMode::Repeating),
+        curve,
+    });
+
+    let background_image = asset_server.load("gametwo/background.png");
+    let texture = asset_server.load("gametwo/ferris_sprite_sheet.png");
+    let layout = TextureAtlasLayout::from_grid(Vec2::new(460.0, 246.0), 3, 1, None, None);
+    let texture_atlas_layout = texture_atlas_layouts.add(layout);
+    // Use only the subset of sprites in the sheet that make up the run animation
+    let animation_indices = AnimationIndices { first: 0, last: 2 };
+    commands.spawn((SpriteBundle {
+        texture: background_image,
+        transform: Transform::from_xyz(0.0, 0.0, 0.0),
+        ..default()
+    }, OnGameScreen));
+    commands.spawn((
+        SpriteBundle {
+            //transform: Transform::from_scale(Vec3::splat(0.5)),
+            sprite: Sprite {
+                custom_size: Some(PLAYER_SIZE),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, PLAYFIELD.bottom() + PLAYER_SIZE.y, 1.0),
+            texture,
+            ..default()
+        },
+        TextureAtlas {
+            layout: texture_atlas_layout,
+            index: animation_indices.first,
+        },
+        animation_indices,
+        AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
+        Walking::default(),
+        Player,
+        OnGameScreen,
+    ));
+    commands.spawn((
+        // Create a TextBundle that has a Text with a single section.
+        hud_text(
+            "Health: ",
+            TextStyle {
+                font_size: 30.0,
+                ..default()
+            },
+        ) // Set the justification of the Text
+        .with_text_justify(JustifyText::Center)
+        // Set the style of the TextBundle itself.
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(30.0),
+            left: Val::Px(30.0),
+            ..default()
+        }),
+        HealthText,
+        OnGameScreen,
+    ));
+    commands.spawn((
+        // Create a TextBundle that has a Text with a single section.
+        hud_text(
+            "Score: ",
+            TextStyle {
+                font_size: 30.0,
+                ..default()
+            },
+        ) // Set the justification of the Text
+        .with_text_justify(JustifyText::Center)
+        // Set the style of the TextBundle itself.
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(30.0),
+            right: Val::Px(30.0),
+            ..default()
+        }),
+        ScoreText,
+        OnGameScreen,
+    ));
+    commands.spawn((
+        hud_text(
+            "Level: ",
+            TextStyle {
+                font_size: 30.0,
+                ..default()
+            },
+        )
+        .with_text_justify(JustifyText::Center)
+        // spans the top of the screen so the text sits in the middle
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(30.0),
+            left: Val::Px(0.0),
+            right: Val::Px(0.0),
+            ..default()
+        }),
+        LevelText,
+        OnGameScreen,
+    ));
+    commands.spawn((
+        hud_text(
+            "Combo: ",
+            TextStyle {
+                font_size: 30.0,
+                ..default()
+            },
+        )
+        .with_text_justify(JustifyText::Center)
+        // under the score
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(70.0),
+            right: Val::Px(30.0),
+            ..default()
+        }),
+        ComboText,
+        OnGameScreen,
+    ));
+    // active power-ups, under the health
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(70.0),
+                    left: Val::Px(30.0),
+                    column_gap: Val::Px(12.0),
+                    ..default()
+                },
+                ..default()
+            },
+            OnGameScreen,
+        ))
+        .with_children(|parent| {
+            for power_up in PowerUp::ALL {
+                parent
+                    .spawn((
+                        NodeBundle {
+                            style: Style {
+                                display: Display::None,
+                                align_items: AlignItems::Center,
+                                column_gap: Val::Px(4.0),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        PowerUpIcon(power_up),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(ImageBundle {
+                            style: Style {
+                                width: Val::Px(40.0),
+                                height: Val::Px(40.0),
+                                ..default()
+                            },
+                            image: UiImage::new(asset_server.load(power_up.icon())),
+                            ..default()
+                        });
+                        parent.spawn((
+                            TextBundle::from_section("", TextStyle { font_size: 26.0, ..default() }),
+                            PowerUpCountdown(power_up),
+                        ));
+                    });
+            }
+        });
+    /*commands.spawn((
+        SpriteBundle {
+            transform: Transform::from_scale(Vec3::splat(0.5)),
+            texture: asset_server.load("elephant.png"),
+            ..default()
+        },
+        Projectile {
+            good: true
+        }
+    ));*/
+}
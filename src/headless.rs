@@ -0,0 +1,147 @@
+// Runs a game without a window or GPU, for automated gameplay tests. The clock only moves when
+// the test advances a frame, and keyboard input is injected as `KeyboardInput` events, so the
+// game's systems see exactly what they would see from a real keyboard.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey};
+use bevy::input::{ButtonState, InputPlugin};
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::{ReceivedCharacter, WindowResized};
+
+use crate::controls::KeyBindings;
+use crate::game_over::RunState;
+use crate::high_scores::HighScores;
+use crate::hud::{Health, Score};
+use crate::minigame::{Minigame, MinigameRegistry, RegisterMinigame};
+use crate::replay::{LastReplay, Playback, Replay, FRAME_TIME};
+use crate::rng::FixedSeed;
+use crate::{shared_plugin, GameState};
+
+// Longest a game may take to load before the test gives up
+const ASSET_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct HeadlessGame {
+    pub app: App,
+}
+
+impl HeadlessGame {
+    // Starts `game` straight away, its setup has run once the first frame is advanced. Tests are
+    // deterministic, every run uses seed 0.
+    pub fn new(game: impl Minigame) -> Self {
+        Self::with_seed(game, 0)
+    }
+
+    pub fn with_seed(game: impl Minigame, seed: u64) -> Self {
+        let state = game.state();
+        let mut app = Self::build_app(seed);
+        app.register_minigame(game);
+        Self::start(app, state)
+    }
+
+    // An app with everything but the games, register them and pass it to `start`
+    pub fn build_app(seed: u64) -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins((AssetPlugin::default(), InputPlugin, TransformPlugin, HierarchyPlugin))
+            // normally registered by the render, sprite and window plugins
+            .init_asset::<Image>()
+            .init_asset::<TextureAtlasLayout>()
+            .add_event::<WindowResized>()
+            .add_event::<ReceivedCharacter>()
+            .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME_TIME))
+            .add_plugins(shared_plugin)
+            .insert_resource(FixedSeed(Some(seed)))
+            // the player's own key bindings and high scores don't apply to tests
+            .insert_resource(KeyBindings::default())
+            .insert_resource(HighScores::default())
+            // the game starts once its assets are in
+            .insert_state(GameState::Menu);
+        app
+    }
+
+    // Starts the game running under `state`, which has to be registered with `app`
+    pub fn start(mut app: App, state: GameState) -> Self {
+        let started = Instant::now();
+        loop {
+            app.update();
+            let registry = app.world.resource::<MinigameRegistry>();
+            if registry.get(state).is_some_and(|game| game.is_loaded(&app.world)) {
+                break;
+            }
+            assert!(started.elapsed() < ASSET_TIMEOUT, "{state:?} didn't finish loading its assets");
+            thread::sleep(Duration::from_millis(1));
+        }
+        app.world.resource_mut::<NextState<GameState>>().set(state);
+        Self { app }
+    }
+
+    // Watches `replay` from the start until the run ends, assert on the result with `score()` etc.
+    pub fn replay(game: impl Minigame, replay: Replay) -> Self {
+        let frames = replay.frame_count();
+        let mut headless = Self::new(game);
+        headless.app.insert_resource(Playback::new(replay));
+        // plus the frame the game over screen comes up on
+        headless.run_frames(frames + 1);
+        headless
+    }
+
+    pub fn world(&mut self) -> &mut World {
+        &mut self.app.world
+    }
+
+    // Advances the game by one frame
+    pub fn step(&mut self) {
+        self.app.update();
+    }
+
+    pub fn run_frames(&mut self, frames: usize) {
+        for _ in 0..frames {
+            self.step();
+        }
+    }
+
+    // The key counts as pressed from the next frame on, until it is released
+    pub fn press(&mut self, key: KeyCode) {
+        self.send_key(key, ButtonState::Pressed);
+    }
+
+    pub fn release(&mut self, key: KeyCode) {
+        self.send_key(key, ButtonState::Released);
+    }
+
+    // Holds `key` down for the given number of frames
+    pub fn hold(&mut self, key: KeyCode, frames: usize) {
+        self.press(key);
+        self.run_frames(frames);
+        self.release(key);
+    }
+
+    fn send_key(&mut self, key: KeyCode, state: ButtonState) {
+        self.app.world.send_event(KeyboardInput {
+            key_code: key,
+            logical_key: Key::Unidentified(NativeKey::Unidentified),
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    pub fn score(&self) -> Option<i32> {
+        self.app.world.get_resource::<Score>().map(|score| score.score)
+    }
+
+    pub fn health(&self) -> Option<i32> {
+        self.app.world.get_resource::<Health>().map(|health| health.health)
+    }
+
+    // Recording of the run, once it has ended
+    pub fn last_replay(&self) -> Option<&Replay> {
+        self.app.world.resource::<LastReplay>().0.as_ref()
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        *self.app.world.resource::<State<RunState>>().get() == RunState::GameOver
+    }
+}
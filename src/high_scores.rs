@@ -0,0 +1,263 @@
+// Top 10 scores of every game, kept in the user's data directory between runs
+
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game_over::RunState;
+use crate::hud::Score;
+use crate::replay::Playback;
+use crate::storage::{load_ron, save_ron};
+use crate::ui::TEXT_COLOR;
+use crate::{in_game, GameState, GameplaySet};
+
+const HIGH_SCORES_FILE: &str = "high_scores.ron";
+pub const MAX_ENTRIES: usize = 10;
+const MAX_NAME_LENGTH: usize = 12;
+const DEFAULT_NAME: &str = "Ferris";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HighScoreEntry {
+    pub name: String,
+    pub score: i32,
+    // seconds since the unix epoch
+    pub date: u64,
+    pub duration_secs: f32,
+}
+
+impl HighScoreEntry {
+    pub fn date_text(&self) -> String {
+        date_text(self.date)
+    }
+
+    // "m:ss"
+    pub fn duration_text(&self) -> String {
+        let secs = self.duration_secs as u64;
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+// Scores keyed by the game id used in `GameState::Playing`, best first
+#[derive(Resource, Serialize, Deserialize, Default)]
+pub struct HighScores {
+    games: BTreeMap<String, Vec<HighScoreEntry>>,
+}
+
+impl HighScores {
+    pub fn load() -> Self {
+        load_ron(HIGH_SCORES_FILE).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        save_ron(HIGH_SCORES_FILE, self);
+    }
+
+    pub fn top(&self, game: &str) -> &[HighScoreEntry] {
+        self.games.get(game).map_or(&[], |entries| entries.as_slice())
+    }
+
+    pub fn qualifies(&self, game: &str, score: i32) -> bool {
+        let top = self.top(game);
+        score > 0 && (top.len() < MAX_ENTRIES || top.iter().any(|entry| score > entry.score))
+    }
+
+    pub fn insert(&mut self, game: &str, entry: HighScoreEntry) {
+        let entries = self.games.entry(game.to_string()).or_default();
+        // ties keep the older entry first
+        let index = entries.partition_point(|other| other.score >= entry.score);
+        entries.insert(index, entry);
+        entries.truncate(MAX_ENTRIES);
+    }
+}
+
+// Time spent in the current run, paused time is not counted
+#[derive(Resource, Default)]
+pub struct RunTime(pub Duration);
+
+// A qualifying score waiting for the player to type their name on the game over screen
+#[derive(Resource)]
+pub struct PendingHighScore {
+    game: &'static str,
+    score: i32,
+    duration: Duration,
+    name: String,
+}
+
+// Text of the name prompt on the game over screen
+#[derive(Component)]
+struct NameEntryText;
+
+pub fn high_scores_plugin(app: &mut App) {
+    app.insert_resource(HighScores::load())
+        .init_resource::<RunTime>()
+        .add_systems(Update, tick_run_time.run_if(in_game).in_set(GameplaySet))
+        .add_systems(Update, reset_run_time)
+        // watching a replay never adds a high score
+        .add_systems(OnEnter(RunState::GameOver), check_high_score.run_if(not(resource_exists::<Playback>)))
+        // leaving the game over screen without pressing Enter still keeps the score
+        .add_systems(OnExit(RunState::GameOver), save_pending_high_score)
+        .add_systems(Update, enter_name.run_if(resource_exists::<PendingHighScore>));
+}
+
+fn tick_run_time(time: Res<Time>, mut run_time: ResMut<RunTime>) {
+    run_time.0 += time.delta();
+}
+
+fn reset_run_time(
+    mut transitions: EventReader<StateTransitionEvent<GameState>>,
+    mut run_time: ResMut<RunTime>,
+) {
+    if !transitions.is_empty() {
+        transitions.clear();
+        run_time.0 = Duration::ZERO;
+    }
+}
+
+// Runs before the game over screen is built, which shows the name prompt if this inserted one
+pub fn check_high_score(
+    mut commands: Commands,
+    game_state: Res<State<GameState>>,
+    high_scores: Res<HighScores>,
+    score: Option<Res<Score>>,
+    run_time: Res<RunTime>,
+) {
+    let (GameState::Playing(game), Some(score)) = (*game_state.get(), score) else {
+        return;
+    };
+    if high_scores.qualifies(game, score.score) {
+        commands.insert_resource(PendingHighScore {
+            game,
+            score: score.score,
+            duration: run_time.0,
+            name: String::new(),
+        });
+    }
+}
+
+// Adds the "New high score!" prompt to the game over screen
+pub fn spawn_name_prompt(parent: &mut ChildBuilder) {
+    parent.spawn((
+        TextBundle::from_sections([
+            TextSection::new(
+                "New high score! Type your name and press Enter: ",
+                TextStyle {
+                    font_size: 25.0,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            ),
+            TextSection::new(
+                "_",
+                TextStyle {
+                    font_size: 25.0,
+                    color: Color::GOLD,
+                    ..default()
+                },
+            ),
+        ])
+        .with_style(Style {
+            margin: UiRect::bottom(Val::Px(20.0)),
+            ..default()
+        }),
+        NameEntryText,
+    ));
+}
+
+fn enter_name(
+    mut commands: Commands,
+    mut characters: EventReader<ReceivedCharacter>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut pending: ResMut<PendingHighScore>,
+    mut high_scores: ResMut<HighScores>,
+    mut text_query: Query<&mut Text, With<NameEntryText>>,
+) {
+    for event in characters.read() {
+        for character in event.char.chars() {
+            if (character.is_alphanumeric() || character == ' ')
+                && pending.name.chars().count() < MAX_NAME_LENGTH
+            {
+                pending.name.push(character);
+            }
+        }
+    }
+    if keyboard.just_pressed(KeyCode::Backspace) {
+        pending.name.pop();
+    }
+
+    if keyboard.just_pressed(KeyCode::Enter) {
+        record(&mut high_scores, &pending);
+        commands.remove_resource::<PendingHighScore>();
+        for mut text in &mut text_query {
+            text.sections[0].value = "Saved as ".to_string();
+            text.sections[1].value = entry_name(&pending.name).to_string();
+        }
+        return;
+    }
+
+    for mut text in &mut text_query {
+        text.sections[1].value = format!("{}_", pending.name);
+    }
+}
+
+fn save_pending_high_score(
+    mut commands: Commands,
+    pending: Option<Res<PendingHighScore>>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    if let Some(pending) = pending {
+        record(&mut high_scores, &pending);
+        commands.remove_resource::<PendingHighScore>();
+    }
+}
+
+fn entry_name(name: &str) -> &str {
+    let name = name.trim();
+    if name.is_empty() {
+        DEFAULT_NAME
+    } else {
+        name
+    }
+}
+
+fn record(high_scores: &mut HighScores, pending: &PendingHighScore) {
+    let date = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    high_scores.insert(
+        pending.game,
+        HighScoreEntry {
+            name: entry_name(&pending.name).to_string(),
+            score: pending.score,
+            date,
+            duration_secs: pending.duration.as_secs_f32(),
+        },
+    );
+}
+
+// Run by the apps with a window whenever a score is added
+pub fn save_high_scores(high_scores: Res<HighScores>) {
+    high_scores.save();
+}
+
+// "YYYY-MM-DD" in UTC, from seconds since the unix epoch
+pub fn date_text(date: u64) -> String {
+    let (year, month, day) = civil_from_days((date / 86_400) as i64);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+// Converts days since 1970-01-01 to a (year, month, day) date, see
+// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+
+// health tracker
+#[derive(Resource, Clone, Copy)]
+pub struct Health {
+    pub health: i32,
+}
+
+impl Health {
+    pub fn is_dead(&self) -> bool {
+        self.health <= 0
+    }
+}
+
+// score tracker
+#[derive(Resource, Clone, Copy, Default)]
+pub struct Score {
+    pub score: i32,
+}
+
+// how far into a run the player is, for games that get harder over time
+#[derive(Resource, Clone, Copy)]
+pub struct Level {
+    pub level: u32,
+}
+
+impl Default for Level {
+    fn default() -> Self {
+        Level { level: 1 }
+    }
+}
+
+// text for health display
+#[derive(Component)]
+pub struct HealthText;
+
+// text for score display
+#[derive(Component)]
+pub struct ScoreText;
+
+// text for level display
+#[derive(Component)]
+pub struct LevelText;
+
+// Keeps the health, score and level texts in sync with the resources of the running game
+pub fn hud_plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            update_health_text.run_if(resource_exists::<Health>),
+            update_score_text.run_if(resource_exists::<Score>),
+            update_level_text.run_if(resource_exists::<Level>),
+        ),
+    );
+}
+
+// A "Label: value" text, the value section is filled in by the HUD systems
+pub fn hud_text(label: &str, style: TextStyle) -> TextBundle {
+    TextBundle::from_sections([
+        TextSection::new(label, style.clone()),
+        TextSection::from_style(style),
+    ])
+}
+
+// update health text
+fn update_health_text(mut query: Query<&mut Text, With<HealthText>>, health: Res<Health>) {
+    for mut text in &mut query {
+        text.sections[1].value = health.health.to_string();
+    }
+}
+
+// update score text
+fn update_score_text(mut query: Query<&mut Text, With<ScoreText>>, score: Res<Score>) {
+    for mut text in &mut query {
+        text.sections[1].value = score.score.to_string();
+    }
+}
+
+// update level text
+fn update_level_text(mut query: Query<&mut Text, With<LevelText>>, level: Res<Level>) {
+    for mut text in &mut query {
+        text.sections[1].value = level.level.to_string();
+    }
+}
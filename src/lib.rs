@@ -0,0 +1,100 @@
+// Shared code for the cRUSTacean recreation launcher and the standalone minigame binaries.
+// Every minigame implements `Minigame` and runs under its own `GameState::Playing` id, so the
+// launcher can host all of them in a single `App`.
+
+use bevy::prelude::*;
+
+pub mod audio;
+pub mod autorunner;
+pub mod cli;
+pub mod collision;
+pub mod controls;
+pub mod crabshooter;
+pub mod difficulty;
+pub mod game_over;
+pub mod gameone;
+pub mod gametwo;
+pub mod headless;
+pub mod high_scores;
+pub mod hud;
+pub mod minigame;
+pub mod pause;
+pub mod playfield;
+pub mod player;
+pub mod replay;
+pub mod rng;
+pub mod settings;
+pub mod storage;
+pub mod ui;
+
+// Enum that will be used as a global state for the game
+#[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Hash, States)]
+pub enum GameState {
+    #[default]
+    Splash,
+    Menu,
+    // A minigame is running, identified by its id
+    Playing(&'static str),
+    // Passed through for a single frame so the game's `OnExit`/`OnEnter` systems run again
+    Restarting(&'static str),
+}
+
+// Systems that advance a game, they stop running while the game is paused or over
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GameplaySet;
+
+// Tag component used to tag entities added on the game screen
+#[derive(Component)]
+pub struct OnGameScreen;
+
+// Run condition: a minigame is running
+pub fn in_game(game_state: Res<State<GameState>>) -> bool {
+    matches!(game_state.get(), GameState::Playing(_))
+}
+
+// Generic system that takes a component as a parameter, and will despawn all entities with that component
+pub fn despawn_screen<T: Component>(to_despawn: Query<Entity, With<T>>, mut commands: Commands) {
+    for entity in &to_despawn {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// Systems shared by every game, added once by the launcher or a standalone binary
+pub fn shared_plugin(app: &mut App) {
+    app.add_plugins((hud::hud_plugin, player::player_plugin, ui::ui_plugin, pause::pause_plugin))
+        .add_plugins((game_over::game_over_plugin, high_scores::high_scores_plugin, rng::rng_plugin))
+        .add_plugins((replay::replay_plugin, controls::controls_plugin, difficulty::difficulty_plugin))
+        // the windowed apps add `audio::audio_plugin` to hear these
+        .add_event::<audio::PlaySfx>()
+        .add_systems(Update, finish_restart);
+}
+
+fn finish_restart(game_state: Res<State<GameState>>, mut next_game_state: ResMut<NextState<GameState>>) {
+    if let GameState::Restarting(id) = *game_state.get() {
+        next_game_state.set(GameState::Playing(id));
+    }
+}
+
+// Used by the standalone binaries: spawns the camera and starts straight into the given game
+pub struct StandalonePlugin(pub GameState);
+
+impl Plugin for StandalonePlugin {
+    fn build(&self, app: &mut App) {
+        let args = cli::CliArgs::from_env();
+        if args.game.is_some() || args.replay.is_some() || args.headless_frames.is_some() {
+            cli::exit_with_error("--game, --replay and --headless only work with the launcher");
+        }
+        app.add_plugins((shared_plugin, replay::realtime_plugin, playfield::playfield_plugin, audio::audio_plugin))
+            .add_plugins(settings::settings_plugin)
+            .insert_state(self.0)
+            .add_systems(Startup, playfield::spawn_camera)
+            .add_systems(Update, bevy::window::close_on_esc)
+            // there is no menu to go back to, so leaving the game closes the window
+            .add_systems(OnEnter(GameState::Menu), quit_app);
+        args.apply(app);
+    }
+}
+
+fn quit_app(mut app_exit_events: EventWriter<bevy::app::AppExit>) {
+    app_exit_events.send(bevy::app::AppExit);
+}
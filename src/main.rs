@@ -2,136 +2,87 @@
 
 use bevy::prelude::*;
 
-const TEXT_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
-
-// Enum that will be used as a global state for the game
-#[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Hash, States)]
-enum GameState {
-    #[default]
-    Splash,
-    Menu,
-    GameOne,
-    GameTwo,
-    GameThree,
-    GameFour,
-}
+use crusty_crab::{
+    audio::audio_plugin, autorunner::CrabRunner, cli::{exit_with_error, run_headless, CliArgs},
+    crabshooter::CrabShooter, despawn_screen, gameone::CatchTheCrab, gametwo::SeafoodScramble,
+    minigame::{MinigameRegistry, RegisterMinigame}, playfield::{playfield_plugin, spawn_camera},
+    replay::{realtime_plugin, Playback}, settings::settings_plugin, shared_plugin, GameState,
+};
 
 fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins)
+    let args = CliArgs::from_env();
+    if args.headless_frames.is_some() {
+        match run_headless(&args, register_games) {
+            Ok(result) => println!("{result}"),
+            Err(error) => exit_with_error(&error),
+        }
+        return;
+    }
+
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins)
         // Declare the game state, whose starting value is determined by the `Default` trait
         .init_state::<GameState>()
-        .add_systems(Startup, setup)
-        .add_systems(Update, bevy::window::close_on_esc)
-        // Adds the plugins for each state
-        .add_plugins(( 
-            menu::menu_plugin, 
-            gameone::gameone_plugin, 
-            gametwo::gametwo_plugin,
-            gamethree::gamethree_plugin,
-            gamefour::gamefour_plugin,
-        ))
-        .run();
-}
+        .add_systems(Startup, (spawn_camera, setup))
+        .add_systems(Update, back_to_menu.run_if(not(in_state(GameState::Menu))))
+        .add_plugins((shared_plugin, realtime_plugin, playfield_plugin, audio_plugin, settings_plugin))
+        .add_plugins(menu::menu_plugin);
+    register_games(&mut app);
+    args.apply(&mut app);
 
-fn setup(mut commands: Commands, mut game_state: ResMut<NextState<GameState>>) {
-    commands.spawn(Camera2dBundle::default());
-    game_state.set(GameState::Menu);
+    // --game and --replay skip the menu
+    let launch = args
+        .launch(app.world.resource::<MinigameRegistry>())
+        .unwrap_or_else(|error| exit_with_error(&error));
+    let start = match launch {
+        Some(launch) => {
+            if let Some(replay) = launch.replay {
+                app.insert_resource(Playback::new(replay));
+            }
+            launch.state
+        }
+        None => GameState::Menu,
+    };
+    app.insert_resource(StartState(start)).run();
 }
 
-// work here maybe?
-mod gameone {
-    use bevy::prelude::*;
-    use std::process::Command;
-
-    use super::{despawn_screen, GameState};
-
-    // GAME ONE PLUGIN
-    pub fn gameone_plugin(app: &mut App) {
-        app.add_systems(OnEnter(GameState::GameOne), gameone)
-            .add_systems(OnExit(GameState::GameOne), despawn_screen::<OnGameScreen>);
-    }
-
-    // Tag component used to tag entities added on the game screen
-    #[derive(Component)]
-    struct OnGameScreen;
-
-    fn gameone(mut game_state: ResMut<NextState<GameState>>) {
-       Command::new("cargo").arg("run").arg("--bin").arg("gameone").output().expect("unable to run game one");
-       game_state.set(GameState::Menu)
-    }
+// Every registered game gets a button in the main menu
+fn register_games(app: &mut App) {
+    app.register_minigame(CatchTheCrab)
+        .register_minigame(SeafoodScramble)
+        .register_minigame(CrabShooter)
+        .register_minigame(CrabRunner);
 }
 
-mod gametwo {
-    use bevy::prelude::*;
-    use std::process::Command;
-
-    use super::{despawn_screen, GameState};
-
-    // GAME TWO PLUGIN
-    pub fn gametwo_plugin(app: &mut App) {
-        app.add_systems(OnEnter(GameState::GameTwo), gametwo)
-            .add_systems(OnExit(GameState::GameTwo), despawn_screen::<OnGameScreen>);
-    }
-
-    // Tag component used to tag entities added on the game screen
-    #[derive(Component)]
-    struct OnGameScreen;
+// State the launcher starts in once it's set up
+#[derive(Resource)]
+struct StartState(GameState);
 
-    fn gametwo(mut game_state: ResMut<NextState<GameState>>) {
-       Command::new("cargo").arg("run").arg("--bin").arg("gametwo").output().expect("unable to run game two");
-       game_state.set(GameState::Menu);
-    }
+fn setup(start: Res<StartState>, mut game_state: ResMut<NextState<GameState>>) {
+    game_state.set(start.0);
 }
 
-mod gamethree {
-    use bevy::prelude::*;
-    use std::process::Command;
-
-    use super::{despawn_screen, GameState};
-
-    // GAME THREE PLUGIN
-    pub fn gamethree_plugin(app: &mut App) {
-        app.add_systems(OnEnter(GameState::GameThree), gameone)
-            .add_systems(OnExit(GameState::GameThree), despawn_screen::<OnGameScreen>);
-    }
-
-    // Tag component used to tag entities added on the game screen
-    #[derive(Component)]
-    struct OnGameScreen;
-
-    fn gameone(mut game_state: ResMut<NextState<GameState>>) {
-       Command::new("cargo").arg("run").arg("--bin").arg("crabshooter").output().expect("unable to run game three");
-       game_state.set(GameState::Menu)
-    }
-}
-
-mod gamefour {
-    use bevy::prelude::*;
-    use std::process::Command;
-
-    use super::{despawn_screen, GameState};
-
-    // GAME FOUR PLUGIN
-    pub fn gamefour_plugin(app: &mut App) {
-        app.add_systems(OnEnter(GameState::GameFour), gameone)
-            .add_systems(OnExit(GameState::GameFour), despawn_screen::<OnGameScreen>);
-    }
-
-    // Tag component used to tag entities added on the game screen
-    #[derive(Component)]
-    struct OnGameScreen;
-
-    fn gameone(mut game_state: ResMut<NextState<GameState>>) {
-       Command::new("cargo").arg("run").arg("--bin").arg("autorunner").output().expect("unable to run game three");
-       game_state.set(GameState::Menu)
+// Esc leaves the running game instead of closing the launcher
+fn back_to_menu(input: Res<ButtonInput<KeyCode>>, mut game_state: ResMut<NextState<GameState>>) {
+    if input.just_pressed(KeyCode::Escape) {
+        game_state.set(GameState::Menu);
     }
 }
 
 mod menu {
-    use bevy::{app::AppExit, prelude::*};
+    use bevy::{app::AppExit, prelude::*, ui::RelativeCursorPosition};
+    use crusty_crab::{
+        audio::AudioSettings,
+        controls::{key_name, Action, KeyBindings},
+        difficulty::Difficulty,
+        high_scores::{date_text, HighScores, MAX_ENTRIES},
+        minigame::MinigameRegistry,
+        replay::{Playback, Replay, ReplaySpeed},
+        settings::{save_settings, DisplayMode, Resolution, Vsync},
+        ui::{spawn_button, title_text, SelectedOption, NORMAL_BUTTON, PRESSED_BUTTON, TEXT_COLOR},
+    };
 
-    use super::{despawn_screen, GameState, TEXT_COLOR};
+    use super::{despawn_screen, GameState};
 
     // This plugin manages the menu
     pub fn menu_plugin(app: &mut App) {
@@ -141,13 +92,69 @@ mod menu {
             // Current screen in the menu is handled by an independent state from `GameState`
             .init_state::<MenuState>()
             .add_systems(OnEnter(GameState::Menu), menu_setup)
+            // Esc closes the launcher from the main menu and goes back to it from the other screens
+            .add_systems(Update, bevy::window::close_on_esc.run_if(in_state(MenuState::Main)))
+            .add_systems(
+                Update,
+                back_to_previous_menu
+                    .before(rebind_key)
+                    .run_if(in_state(GameState::Menu))
+                    .run_if(not(in_state(MenuState::Main))),
+            )
             // Systems to handle the main menu screen
             .add_systems(OnEnter(MenuState::Main), main_menu_setup)
             .add_systems(OnExit(MenuState::Main), despawn_screen::<OnMainMenuScreen>)
-            // Common systems to all screens that handles buttons behavior
+            // Systems to handle the high scores screen
+            .add_systems(OnEnter(MenuState::HighScores), high_scores_menu_setup)
+            .add_systems(OnExit(MenuState::HighScores), despawn_screen::<OnHighScoresMenuScreen>)
+            // Systems to handle the replays screen
+            .add_systems(OnEnter(MenuState::Replays), replays_menu_setup)
+            .add_systems(OnExit(MenuState::Replays), despawn_screen::<OnReplaysMenuScreen>)
+            .add_systems(
+                Update,
+                setting_button::<ReplaySpeed>.run_if(in_state(MenuState::Replays)),
+            )
+            // Systems to handle the settings screen, which links to a page for each kind of setting
+            .add_systems(OnEnter(MenuState::Settings), settings_menu_setup)
+            .add_systems(OnExit(MenuState::Settings), despawn_screen::<OnSettingsMenuScreen>)
+            // Systems to handle the display settings page
+            .add_systems(OnEnter(MenuState::Display), display_menu_setup)
+            .add_systems(OnExit(MenuState::Display), (despawn_screen::<OnDisplayMenuScreen>, save_settings))
             .add_systems(
                 Update,
-                (menu_action, button_system).run_if(in_state(GameState::Menu)),
+                (setting_button::<DisplayMode>, setting_button::<Vsync>, setting_button::<Resolution>)
+                    .run_if(in_state(MenuState::Display)),
+            )
+            // Systems to handle the gameplay settings page
+            .add_systems(OnEnter(MenuState::Gameplay), gameplay_menu_setup)
+            .add_systems(OnExit(MenuState::Gameplay), (despawn_screen::<OnGameplayMenuScreen>, save_settings))
+            .add_systems(
+                Update,
+                setting_button::<Difficulty>.run_if(in_state(MenuState::Gameplay)),
+            )
+            // Systems to handle the controls screen
+            .init_resource::<Rebinding>()
+            .add_systems(OnEnter(MenuState::Controls), controls_menu_setup)
+            .add_systems(OnExit(MenuState::Controls), (despawn_screen::<OnControlsMenuScreen>, stop_rebinding))
+            .add_systems(
+                Update,
+                (rebind_key, update_binding_texts)
+                    .chain()
+                    .run_if(in_state(MenuState::Controls)),
+            )
+            // Systems to handle the audio screen, the settings are saved when it is closed
+            .add_systems(OnEnter(MenuState::Audio), audio_menu_setup)
+            .add_systems(OnExit(MenuState::Audio), (despawn_screen::<OnAudioMenuScreen>, save_audio_settings))
+            .add_systems(
+                Update,
+                (drag_volume_sliders, update_audio_widgets)
+                    .chain()
+                    .run_if(in_state(MenuState::Audio)),
+            )
+            // Button colours are handled by `crusty_crab::ui`, this handles what the buttons do
+            .add_systems(
+                Update,
+                menu_action.run_if(in_state(GameState::Menu)),
             );
     }
 
@@ -155,6 +162,13 @@ mod menu {
     #[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Hash, States)]
     enum MenuState {
         Main,
+        HighScores,
+        Replays,
+        Settings,
+        Display,
+        Audio,
+        Controls,
+        Gameplay,
         #[default]
         Disabled,
     }
@@ -163,53 +177,132 @@ mod menu {
     #[derive(Component)]
     struct OnMainMenuScreen;
 
-    const NORMAL_BUTTON: Color = Color::rgb(0.15, 0.15, 0.15);
-    const HOVERED_BUTTON: Color = Color::rgb(0.25, 0.25, 0.25);
-    const HOVERED_PRESSED_BUTTON: Color = Color::rgb(0.25, 0.65, 0.25);
-    const PRESSED_BUTTON: Color = Color::rgb(0.35, 0.75, 0.35);
+    // Tag component used to tag entities added on the high scores screen
+    #[derive(Component)]
+    struct OnHighScoresMenuScreen;
 
-    // Tag component used to mark which setting is currently selected
+    // Tag component used to tag entities added on the replays screen
     #[derive(Component)]
-    struct SelectedOption;
+    struct OnReplaysMenuScreen;
 
-    // All actions that can be triggered from a button click
-    // GAME BUTTONS
+    // Tag component used to tag entities added on the settings screen
     #[derive(Component)]
-    enum MenuButtonAction {
-        PlayOne,
-        PlayTwo,
-        PlayThree,
-        PlayFour,
-        Quit,
+    struct OnSettingsMenuScreen;
+
+    // Tag component used to tag entities added on the display settings page
+    #[derive(Component)]
+    struct OnDisplayMenuScreen;
+
+    // Tag component used to tag entities added on the gameplay settings page
+    #[derive(Component)]
+    struct OnGameplayMenuScreen;
+
+    // Tag component used to tag entities added on the controls screen
+    #[derive(Component)]
+    struct OnControlsMenuScreen;
+
+    // Tag component used to tag entities added on the audio screen
+    #[derive(Component)]
+    struct OnAudioMenuScreen;
+
+    // A volume slider on the audio screen, clicking or dragging along it sets the volume
+    #[derive(Component, Clone, Copy)]
+    enum VolumeSlider {
+        Master,
+        Music,
+        Effects,
     }
 
-    // This system handles changing all buttons color based on mouse interaction
-    fn button_system(
-        mut interaction_query: Query<
-            (&Interaction, &mut BackgroundColor, Option<&SelectedOption>),
-            (Changed<Interaction>, With<Button>),
-        >,
-    ) {
-        for (interaction, mut color, selected) in &mut interaction_query {
-            *color = match (*interaction, selected) {
-                (Interaction::Pressed, _) | (Interaction::None, Some(_)) => PRESSED_BUTTON.into(),
-                (Interaction::Hovered, Some(_)) => HOVERED_PRESSED_BUTTON.into(),
-                (Interaction::Hovered, None) => HOVERED_BUTTON.into(),
-                (Interaction::None, None) => NORMAL_BUTTON.into(),
+    impl VolumeSlider {
+        const ALL: [VolumeSlider; 3] = [VolumeSlider::Master, VolumeSlider::Music, VolumeSlider::Effects];
+
+        fn label(self) -> &'static str {
+            match self {
+                VolumeSlider::Master => "Master",
+                VolumeSlider::Music => "Music",
+                VolumeSlider::Effects => "Effects",
+            }
+        }
+
+        fn volume(self, settings: &AudioSettings) -> f32 {
+            match self {
+                VolumeSlider::Master => settings.master,
+                VolumeSlider::Music => settings.music,
+                VolumeSlider::Effects => settings.sfx,
             }
         }
+
+        fn set_volume(self, settings: &mut AudioSettings, volume: f32) {
+            match self {
+                VolumeSlider::Master => settings.master = volume,
+                VolumeSlider::Music => settings.music = volume,
+                VolumeSlider::Effects => settings.sfx = volume,
+            }
+        }
+    }
+
+    // The filled part of a volume slider
+    #[derive(Component)]
+    struct SliderFill(VolumeSlider);
+
+    // The percentage next to a volume slider
+    #[derive(Component)]
+    struct SliderValueText(VolumeSlider);
+
+    #[derive(Component)]
+    struct MuteText;
+
+    // A game's action, shown on the controls screen with the key it is bound to
+    #[derive(Component, Clone, Copy, PartialEq)]
+    struct BindingText {
+        game: &'static str,
+        action: Action,
+    }
+
+    // The action waiting for a key press after its button was clicked
+    #[derive(Resource, Default)]
+    struct Rebinding(Option<BindingText>);
+
+    // Replays listed on the replays screen, loaded when it is opened
+    #[derive(Resource)]
+    struct ReplayList(Vec<Replay>);
+
+    // All actions that can be triggered from a button click
+    // GAME BUTTONS
+    #[derive(Component)]
+    enum MenuButtonAction {
+        Play(GameState),
+        HighScores,
+        Replays,
+        // index into `ReplayList`
+        WatchReplay(usize),
+        Settings,
+        Display,
+        Audio,
+        Controls,
+        Gameplay,
+        Rebind(BindingText),
+        ResetControls,
+        ToggleMute,
+        BackToSettings,
+        BackToMainMenu,
+        Quit,
     }
 
     fn menu_setup(mut menu_state: ResMut<NextState<MenuState>>) {
         menu_state.set(MenuState::Main);
     }
 
-    fn main_menu_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    fn main_menu_setup(
+        mut commands: Commands,
+        asset_server: Res<AssetServer>,
+        registry: Res<MinigameRegistry>,
+    ) {
         // Common style for all buttons on the screen
         let button_style = Style {
             width: Val::Px(275.0),
             height: Val::Px(90.0),
-            margin: UiRect::all(Val::Px(20.0)),
+            margin: UiRect::all(Val::Px(15.0)),
             justify_content: JustifyContent::Center,
             align_items: AlignItems::Center,
             ..default()
@@ -227,6 +320,11 @@ mod menu {
             color: TEXT_COLOR,
             ..default()
         };
+        let button_description_style = TextStyle {
+            font_size: 16.0,
+            color: TEXT_COLOR,
+            ..default()
+        };
 
         commands
             .spawn((
@@ -265,127 +363,795 @@ mod menu {
                                 },
                             )
                             .with_style(Style {
-                                margin: UiRect::all(Val::Px(50.0)),
+                                margin: UiRect::all(Val::Px(40.0)),
                                 ..default()
                             }),
                         );
 
-                        // GAME BUTTONS
+                        // GAME BUTTONS, two per row
                         parent
-                            .spawn((
-                                ButtonBundle {
-                                    style: button_style.clone(),
-                                    background_color: NORMAL_BUTTON.into(),
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    flex_wrap: FlexWrap::Wrap,
+                                    justify_content: JustifyContent::Center,
+                                    max_width: Val::Px(650.0),
                                     ..default()
                                 },
-                                MenuButtonAction::PlayOne,
-                            ))
+                                ..default()
+                            })
                             .with_children(|parent| {
-                                let icon = asset_server.load("textures/Game Icons/right.png");
-                                parent.spawn(ImageBundle {
-                                    style: button_icon_style.clone(),
-                                    image: UiImage::new(icon),
-                                    ..default()
-                                });
-                                parent.spawn(TextBundle::from_section(
-                                    "Catch the Crab",
-                                    button_text_style.clone(),
-                                ));
+                                for game in registry.iter() {
+                                    parent
+                                        .spawn((
+                                            ButtonBundle {
+                                                style: Style {
+                                                    flex_direction: FlexDirection::Column,
+                                                    ..button_style.clone()
+                                                },
+                                                background_color: NORMAL_BUTTON.into(),
+                                                ..default()
+                                            },
+                                            MenuButtonAction::Play(game.state()),
+                                        ))
+                                        .with_children(|parent| {
+                                            let icon = asset_server.load(game.icon());
+                                            parent.spawn(ImageBundle {
+                                                style: button_icon_style.clone(),
+                                                image: UiImage::new(icon),
+                                                ..default()
+                                            });
+                                            parent.spawn(TextBundle::from_section(
+                                                game.name(),
+                                                button_text_style.clone(),
+                                            ));
+                                            parent.spawn(TextBundle::from_section(
+                                                game.description(),
+                                                button_description_style.clone(),
+                                            ));
+                                        });
+                                }
                             });
+
                         parent
-                            .spawn((
-                                ButtonBundle {
-                                    style: button_style.clone(),
-                                    background_color: NORMAL_BUTTON.into(),
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    margin: UiRect::bottom(Val::Px(20.0)),
                                     ..default()
                                 },
-                                MenuButtonAction::PlayTwo,
-                            ))
+                                ..default()
+                            })
                             .with_children(|parent| {
-                                let icon = asset_server.load("textures/Game Icons/right.png");
-                                parent.spawn(ImageBundle {
-                                    style: button_icon_style.clone(),
-                                    image: UiImage::new(icon),
+                                spawn_button(parent, "High Scores", MenuButtonAction::HighScores);
+                                spawn_button(parent, "Replays", MenuButtonAction::Replays);
+                                spawn_button(parent, "Settings", MenuButtonAction::Settings);
+                                spawn_button(parent, "Quit", MenuButtonAction::Quit);
+                            });
+                    });
+            });
+    }
+
+    fn high_scores_menu_setup(
+        mut commands: Commands,
+        registry: Res<MinigameRegistry>,
+        high_scores: Res<HighScores>,
+    ) {
+        let heading_style = TextStyle {
+            font_size: 30.0,
+            color: TEXT_COLOR,
+            ..default()
+        };
+        let entry_style = TextStyle {
+            font_size: 14.0,
+            color: TEXT_COLOR,
+            ..default()
+        };
+
+        commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    background_color: Color::CRIMSON.into(),
+                    ..default()
+                },
+                OnHighScoresMenuScreen,
+            ))
+            .with_children(|parent| {
+                parent.spawn(title_text("High Scores"));
+
+                // One column per game
+                parent
+                    .spawn(NodeBundle::default())
+                    .with_children(|parent| {
+                        for game in registry.iter() {
+                            let GameState::Playing(id) = game.state() else {
+                                continue;
+                            };
+                            parent
+                                .spawn(NodeBundle {
+                                    style: Style {
+                                        flex_direction: FlexDirection::Column,
+                                        width: Val::Px(300.0),
+                                        margin: UiRect::horizontal(Val::Px(5.0)),
+                                        ..default()
+                                    },
                                     ..default()
+                                })
+                                .with_children(|parent| {
+                                    parent.spawn(
+                                        TextBundle::from_section(game.name(), heading_style.clone())
+                                            .with_style(Style {
+                                                margin: UiRect::bottom(Val::Px(10.0)),
+                                                ..default()
+                                            }),
+                                    );
+                                    let entries = high_scores.top(id);
+                                    if entries.is_empty() {
+                                        parent.spawn(TextBundle::from_section(
+                                            "No scores yet",
+                                            entry_style.clone(),
+                                        ));
+                                    }
+                                    for (rank, entry) in entries.iter().take(MAX_ENTRIES).enumerate() {
+                                        parent.spawn(TextBundle::from_section(
+                                            format!(
+                                                "{:>2}. {:<12} {:>5}  {}  {}",
+                                                rank + 1,
+                                                entry.name,
+                                                entry.score,
+                                                entry.date_text(),
+                                                entry.duration_text(),
+                                            ),
+                                            entry_style.clone(),
+                                        ));
+                                    }
                                 });
-                                parent.spawn(TextBundle::from_section(
-                                    "Seafood Scramble",
-                                    button_text_style.clone(),
-                                ));
-                            });
-                        parent
-                            .spawn((
+                        }
+                    });
+
+                spawn_button(parent, "Back", MenuButtonAction::BackToMainMenu);
+            });
+    }
+
+    fn replays_menu_setup(
+        mut commands: Commands,
+        registry: Res<MinigameRegistry>,
+        speed: Res<ReplaySpeed>,
+    ) {
+        let replays = Replay::load_all();
+        let replay_button_style = Style {
+            width: Val::Px(400.0),
+            height: Val::Px(30.0),
+            margin: UiRect::all(Val::Px(3.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        };
+        let replay_text_style = TextStyle {
+            font_size: 16.0,
+            color: TEXT_COLOR,
+            ..default()
+        };
+        let speed_button_style = Style {
+            width: Val::Px(80.0),
+            height: Val::Px(50.0),
+            margin: UiRect::all(Val::Px(10.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        };
+        let speed_text_style = TextStyle {
+            font_size: 30.0,
+            color: TEXT_COLOR,
+            ..default()
+        };
+
+        commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    background_color: Color::CRIMSON.into(),
+                    ..default()
+                },
+                OnReplaysMenuScreen,
+            ))
+            .with_children(|parent| {
+                parent.spawn(title_text("Replays"));
+
+                if replays.is_empty() {
+                    parent.spawn(TextBundle::from_section(
+                        "No replays yet, every finished run is recorded",
+                        replay_text_style.clone(),
+                    ));
+                }
+                // Newest first, two per row
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_wrap: FlexWrap::Wrap,
+                            justify_content: JustifyContent::Center,
+                            max_width: Val::Px(820.0),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        for (index, replay) in replays.iter().enumerate() {
+                            let name = registry.by_id(&replay.game).map_or(replay.game.as_str(), |game| game.name());
+                            parent
+                                .spawn((
+                                    ButtonBundle {
+                                        style: replay_button_style.clone(),
+                                        background_color: NORMAL_BUTTON.into(),
+                                        ..default()
+                                    },
+                                    MenuButtonAction::WatchReplay(index),
+                                ))
+                                .with_children(|parent| {
+                                    parent.spawn(TextBundle::from_section(
+                                        format!("{name}  {}  score {}", date_text(replay.date), replay.final_score),
+                                        replay_text_style.clone(),
+                                    ));
+                                });
+                        }
+                    });
+
+                // Playback speed
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            align_items: AlignItems::Center,
+                            margin: UiRect::top(Val::Px(20.0)),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section("Speed", speed_text_style.clone()));
+                        for speed_setting in [ReplaySpeed(1), ReplaySpeed(2), ReplaySpeed(4)] {
+                            let mut entity = parent.spawn((
                                 ButtonBundle {
-                                    style: button_style.clone(),
+                                    style: speed_button_style.clone(),
                                     background_color: NORMAL_BUTTON.into(),
                                     ..default()
                                 },
-                                MenuButtonAction::PlayThree,
-                            ))
-                            .with_children(|parent| {
-                                let icon = asset_server.load("textures/Game Icons/right.png");
-                                parent.spawn(ImageBundle {
-                                    style: button_icon_style.clone(),
-                                    image: UiImage::new(icon),
-                                    ..default()
-                                });
+                                speed_setting,
+                            ));
+                            entity.with_children(|parent| {
                                 parent.spawn(TextBundle::from_section(
-                                    "Crab Shooter",
-                                    button_text_style.clone(),
+                                    format!("{}x", speed_setting.0),
+                                    speed_text_style.clone(),
                                 ));
                             });
-                        parent
-                            .spawn((
-                                ButtonBundle {
-                                    style: button_style.clone(),
-                                    background_color: NORMAL_BUTTON.into(),
+                            if *speed == speed_setting {
+                                entity.insert((SelectedOption, BackgroundColor(PRESSED_BUTTON)));
+                            }
+                        }
+                    });
+
+                spawn_button(parent, "Back", MenuButtonAction::BackToMainMenu);
+            });
+        commands.insert_resource(ReplayList(replays));
+    }
+
+    fn settings_menu_setup(mut commands: Commands) {
+        commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    background_color: Color::CRIMSON.into(),
+                    ..default()
+                },
+                OnSettingsMenuScreen,
+            ))
+            .with_children(|parent| {
+                parent.spawn(title_text("Settings"));
+                spawn_button(parent, "Display", MenuButtonAction::Display);
+                spawn_button(parent, "Audio", MenuButtonAction::Audio);
+                spawn_button(parent, "Controls", MenuButtonAction::Controls);
+                spawn_button(parent, "Gameplay", MenuButtonAction::Gameplay);
+                spawn_button(parent, "Back", MenuButtonAction::BackToMainMenu);
+            });
+    }
+
+    fn display_menu_setup(
+        mut commands: Commands,
+        display_mode: Res<DisplayMode>,
+        vsync: Res<Vsync>,
+        resolution: Res<Resolution>,
+    ) {
+        commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    background_color: Color::CRIMSON.into(),
+                    ..default()
+                },
+                OnDisplayMenuScreen,
+            ))
+            .with_children(|parent| {
+                parent.spawn(title_text("Display"));
+                spawn_setting_row(parent, "Window", &DisplayMode::ALL, *display_mode, |mode| mode.label().to_string());
+                spawn_setting_row(parent, "Vsync", &Vsync::ALL, *vsync, |vsync| vsync.label().to_string());
+                // only used in windowed mode, a size given with --size gets a button of its own
+                let mut resolutions = Resolution::ALL.to_vec();
+                if !resolutions.contains(&resolution) {
+                    resolutions.push(*resolution);
+                }
+                spawn_setting_row(parent, "Resolution", &resolutions, *resolution, Resolution::label);
+                spawn_button(parent, "Back", MenuButtonAction::BackToSettings);
+            });
+    }
+
+    fn gameplay_menu_setup(mut commands: Commands, difficulty: Res<Difficulty>) {
+        commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    background_color: Color::CRIMSON.into(),
+                    ..default()
+                },
+                OnGameplayMenuScreen,
+            ))
+            .with_children(|parent| {
+                parent.spawn(title_text("Gameplay"));
+                // Difficulty of the next run
+                spawn_setting_row(parent, "Difficulty", &Difficulty::ALL, *difficulty, |difficulty| {
+                    difficulty.label().to_string()
+                });
+                spawn_button(parent, "Back", MenuButtonAction::BackToSettings);
+            });
+    }
+
+    // A labelled row with a button for each option of a setting, the current one is selected.
+    // `setting_button::<T>` handles the clicks.
+    fn spawn_setting_row<T: Component + PartialEq + Copy>(
+        parent: &mut ChildBuilder,
+        label: &str,
+        options: &[T],
+        current: T,
+        option_label: impl Fn(T) -> String,
+    ) {
+        let text_style = TextStyle {
+            font_size: 30.0,
+            color: TEXT_COLOR,
+            ..default()
+        };
+        let button_style = Style {
+            width: Val::Px(200.0),
+            height: Val::Px(50.0),
+            margin: UiRect::all(Val::Px(10.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        };
+
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(label, text_style.clone()).with_style(Style {
+                    width: Val::Px(170.0),
+                    ..default()
+                }));
+                for &option in options {
+                    let mut entity = parent.spawn((
+                        ButtonBundle {
+                            style: button_style.clone(),
+                            background_color: NORMAL_BUTTON.into(),
+                            ..default()
+                        },
+                        option,
+                    ));
+                    entity.with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(option_label(option), text_style.clone()));
+                    });
+                    if option == current {
+                        entity.insert((SelectedOption, BackgroundColor(PRESSED_BUTTON)));
+                    }
+                }
+            });
+    }
+
+    fn controls_menu_setup(mut commands: Commands, registry: Res<MinigameRegistry>) {
+        let heading_style = TextStyle {
+            font_size: 30.0,
+            color: TEXT_COLOR,
+            ..default()
+        };
+        let binding_style = TextStyle {
+            font_size: 18.0,
+            color: TEXT_COLOR,
+            ..default()
+        };
+        let binding_button_style = Style {
+            width: Val::Px(240.0),
+            height: Val::Px(35.0),
+            margin: UiRect::all(Val::Px(3.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        };
+
+        commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    background_color: Color::CRIMSON.into(),
+                    ..default()
+                },
+                OnControlsMenuScreen,
+            ))
+            .with_children(|parent| {
+                parent.spawn(title_text("Controls"));
+                parent.spawn(TextBundle::from_section(
+                    "Click an action, then press its new key. Gamepads always work too.",
+                    binding_style.clone(),
+                ));
+
+                // One column per game, the key names are filled in by `update_binding_texts`
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            margin: UiRect::vertical(Val::Px(20.0)),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        for game in registry.iter() {
+                            let GameState::Playing(id) = game.state() else {
+                                continue;
+                            };
+                            parent
+                                .spawn(NodeBundle {
+                                    style: Style {
+                                        flex_direction: FlexDirection::Column,
+                                        align_items: AlignItems::Center,
+                                        width: Val::Px(260.0),
+                                        ..default()
+                                    },
                                     ..default()
-                                },
-                                MenuButtonAction::PlayFour,
-                            ))
-                            .with_children(|parent| {
-                                let icon = asset_server.load("textures/Game Icons/right.png");
-                                parent.spawn(ImageBundle {
-                                    style: button_icon_style.clone(),
-                                    image: UiImage::new(icon),
+                                })
+                                .with_children(|parent| {
+                                    parent.spawn(
+                                        TextBundle::from_section(game.name(), heading_style.clone())
+                                            .with_style(Style {
+                                                margin: UiRect::bottom(Val::Px(10.0)),
+                                                ..default()
+                                            }),
+                                    );
+                                    let actions = game.controls().iter().map(|&(action, _)| action);
+                                    for action in actions.chain([Action::Pause]) {
+                                        let binding = BindingText { game: id, action };
+                                        parent
+                                            .spawn((
+                                                ButtonBundle {
+                                                    style: binding_button_style.clone(),
+                                                    background_color: NORMAL_BUTTON.into(),
+                                                    ..default()
+                                                },
+                                                MenuButtonAction::Rebind(binding),
+                                            ))
+                                            .with_children(|parent| {
+                                                parent.spawn((
+                                                    TextBundle::from_section("", binding_style.clone()),
+                                                    binding,
+                                                ));
+                                            });
+                                    }
+                                });
+                        }
+                    });
+
+                parent
+                    .spawn(NodeBundle::default())
+                    .with_children(|parent| {
+                        spawn_button(parent, "Reset", MenuButtonAction::ResetControls);
+                        spawn_button(parent, "Back", MenuButtonAction::BackToSettings);
+                    });
+            });
+    }
+
+    // Escape cancels, any other key becomes the new binding
+    fn rebind_key(
+        keyboard: Res<ButtonInput<KeyCode>>,
+        mut rebinding: ResMut<Rebinding>,
+        mut bindings: ResMut<KeyBindings>,
+    ) {
+        let Some(binding) = rebinding.0 else {
+            return;
+        };
+        let Some(&key) = keyboard.get_just_pressed().next() else {
+            return;
+        };
+        if key != KeyCode::Escape {
+            bindings.rebind(binding.game, binding.action, key);
+            bindings.save();
+        }
+        rebinding.0 = None;
+    }
+
+    fn stop_rebinding(mut rebinding: ResMut<Rebinding>) {
+        rebinding.0 = None;
+    }
+
+    fn update_binding_texts(
+        rebinding: Res<Rebinding>,
+        bindings: Res<KeyBindings>,
+        registry: Res<MinigameRegistry>,
+        mut text_query: Query<(&mut Text, &BindingText)>,
+        added: Query<(), Added<BindingText>>,
+    ) {
+        if !(rebinding.is_changed() || bindings.is_changed() || !added.is_empty()) {
+            return;
+        }
+        for (mut text, binding) in &mut text_query {
+            let key = registry
+                .by_id(binding.game)
+                .map(|game| bindings.for_game(binding.game, game.controls()))
+                .and_then(|keys| keys.into_iter().find(|&(action, _)| action == binding.action))
+                .map(|(_, key)| key);
+            text.sections[0].value = match key {
+                _ if rebinding.0 == Some(*binding) => format!("{}: press a key", binding.action.label()),
+                Some(key) => format!("{}: {}", binding.action.label(), key_name(key)),
+                None => binding.action.label().to_string(),
+            };
+        }
+    }
+
+    fn audio_menu_setup(mut commands: Commands) {
+        let text_style = TextStyle {
+            font_size: 30.0,
+            color: TEXT_COLOR,
+            ..default()
+        };
+
+        commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    background_color: Color::CRIMSON.into(),
+                    ..default()
+                },
+                OnAudioMenuScreen,
+            ))
+            .with_children(|parent| {
+                parent.spawn(title_text("Audio"));
+
+                for slider in VolumeSlider::ALL {
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                align_items: AlignItems::Center,
+                                margin: UiRect::all(Val::Px(10.0)),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn(
+                                TextBundle::from_section(slider.label(), text_style.clone()).with_style(Style {
+                                    width: Val::Px(150.0),
                                     ..default()
+                                }),
+                            );
+                            // The track is a button so it gets clicks, `RelativeCursorPosition`
+                            // tells where along it the cursor is
+                            parent
+                                .spawn((
+                                    ButtonBundle {
+                                        style: Style {
+                                            width: Val::Px(400.0),
+                                            height: Val::Px(30.0),
+                                            ..default()
+                                        },
+                                        background_color: NORMAL_BUTTON.into(),
+                                        ..default()
+                                    },
+                                    RelativeCursorPosition::default(),
+                                    slider,
+                                ))
+                                .with_children(|parent| {
+                                    parent.spawn((
+                                        NodeBundle {
+                                            style: Style {
+                                                height: Val::Percent(100.0),
+                                                ..default()
+                                            },
+                                            background_color: PRESSED_BUTTON.into(),
+                                            ..default()
+                                        },
+                                        SliderFill(slider),
+                                    ));
                                 });
-                                parent.spawn(TextBundle::from_section(
-                                    "Crab Autorunner",
-                                    button_text_style.clone(),
-                                ));
-                            });
-                        
+                            parent.spawn((
+                                TextBundle::from_section("", text_style.clone()).with_style(Style {
+                                    width: Val::Px(100.0),
+                                    margin: UiRect::left(Val::Px(20.0)),
+                                    ..default()
+                                }),
+                                SliderValueText(slider),
+                            ));
+                        });
+                }
+
+                parent
+                    .spawn(NodeBundle::default())
+                    .with_children(|parent| {
                         parent
                             .spawn((
                                 ButtonBundle {
-                                    style: button_style,
+                                    style: Style {
+                                        width: Val::Px(275.0),
+                                        height: Val::Px(65.0),
+                                        margin: UiRect::all(Val::Px(10.0)),
+                                        justify_content: JustifyContent::Center,
+                                        align_items: AlignItems::Center,
+                                        ..default()
+                                    },
                                     background_color: NORMAL_BUTTON.into(),
                                     ..default()
                                 },
-                                MenuButtonAction::Quit,
+                                MenuButtonAction::ToggleMute,
                             ))
                             .with_children(|parent| {
-                                let icon = asset_server.load("textures/Game Icons/exitRight.png");
-                                parent.spawn(ImageBundle {
-                                    style: button_icon_style,
-                                    image: UiImage::new(icon),
-                                    ..default()
-                                });
-                                parent.spawn(TextBundle::from_section("Quit", button_text_style));
+                                parent.spawn((TextBundle::from_section("", text_style.clone()), MuteText));
                             });
+                        spawn_button(parent, "Back", MenuButtonAction::BackToSettings);
                     });
             });
     }
 
+    // Holding the mouse button down on a slider keeps following the cursor while it's dragged
+    fn drag_volume_sliders(
+        sliders: Query<(&Interaction, &RelativeCursorPosition, &VolumeSlider)>,
+        mut settings: ResMut<AudioSettings>,
+    ) {
+        for (interaction, cursor, slider) in &sliders {
+            if *interaction != Interaction::Pressed {
+                continue;
+            }
+            let Some(position) = cursor.normalized else {
+                continue;
+            };
+            let volume = position.x.clamp(0.0, 1.0);
+            if slider.volume(&settings) != volume {
+                slider.set_volume(&mut settings, volume);
+            }
+        }
+    }
+
+    fn update_audio_widgets(
+        settings: Res<AudioSettings>,
+        mut fills: Query<(&mut Style, &SliderFill)>,
+        mut value_texts: Query<(&mut Text, &SliderValueText), Without<MuteText>>,
+        mut mute_texts: Query<&mut Text, With<MuteText>>,
+        added: Query<(), Added<MuteText>>,
+    ) {
+        if !settings.is_changed() && added.is_empty() {
+            return;
+        }
+        for (mut style, fill) in &mut fills {
+            style.width = Val::Percent(fill.0.volume(&settings) * 100.0);
+        }
+        for (mut text, value_text) in &mut value_texts {
+            text.sections[0].value = format!("{:.0}%", value_text.0.volume(&settings) * 100.0);
+        }
+        for mut text in &mut mute_texts {
+            text.sections[0].value = if settings.muted { "Sound: Off" } else { "Sound: On" }.to_string();
+        }
+    }
+
+    fn save_audio_settings(settings: Res<AudioSettings>) {
+        settings.save();
+    }
+
+    // The settings pages go back to the settings screen, everything else to the main menu
+    fn back_to_previous_menu(
+        keyboard: Res<ButtonInput<KeyCode>>,
+        rebinding: Res<Rebinding>,
+        current_menu: Res<State<MenuState>>,
+        mut menu_state: ResMut<NextState<MenuState>>,
+    ) {
+        if keyboard.just_pressed(KeyCode::Escape) && rebinding.0.is_none() {
+            menu_state.set(match current_menu.get() {
+                MenuState::Display | MenuState::Audio | MenuState::Controls | MenuState::Gameplay => {
+                    MenuState::Settings
+                }
+                _ => MenuState::Main,
+            });
+        }
+    }
+
+    // This system updates the settings when a new value for a setting is selected, and marks
+    // the button as the one currently selected. Each setting has its own selected button, so
+    // several can share a screen.
+    fn setting_button<T: Resource + Component + PartialEq + Copy>(
+        interaction_query: Query<(&Interaction, &T, Entity), (Changed<Interaction>, With<Button>)>,
+        mut selected_query: Query<(Entity, &mut BackgroundColor), (With<SelectedOption>, With<T>)>,
+        mut commands: Commands,
+        mut setting: ResMut<T>,
+    ) {
+        for (interaction, button_setting, entity) in &interaction_query {
+            if *interaction == Interaction::Pressed && *setting != *button_setting {
+                for (previous_button, mut previous_color) in &mut selected_query {
+                    *previous_color = NORMAL_BUTTON.into();
+                    commands.entity(previous_button).remove::<SelectedOption>();
+                }
+                commands.entity(entity).insert(SelectedOption);
+                *setting = *button_setting;
+            }
+        }
+    }
+
     fn menu_action(
         interaction_query: Query<
             (&Interaction, &MenuButtonAction),
             (Changed<Interaction>, With<Button>),
         >,
+        mut commands: Commands,
         mut app_exit_events: EventWriter<AppExit>,
         mut menu_state: ResMut<NextState<MenuState>>,
         mut game_state: ResMut<NextState<GameState>>,
+        registry: Res<MinigameRegistry>,
+        replay_list: Option<Res<ReplayList>>,
+        mut rebinding: ResMut<Rebinding>,
+        mut bindings: ResMut<KeyBindings>,
+        mut audio_settings: ResMut<AudioSettings>,
     ) {
         for (interaction, menu_button_action) in &interaction_query {
             if *interaction == Interaction::Pressed {
@@ -393,32 +1159,39 @@ mod menu {
                     MenuButtonAction::Quit => {
                         app_exit_events.send(AppExit);
                     }
-                    // ENTERS GAMES
-                    MenuButtonAction::PlayOne => {
-                        game_state.set(GameState::GameOne);
-                        menu_state.set(MenuState::Disabled);
+                    MenuButtonAction::HighScores => menu_state.set(MenuState::HighScores),
+                    MenuButtonAction::Replays => menu_state.set(MenuState::Replays),
+                    MenuButtonAction::Settings => menu_state.set(MenuState::Settings),
+                    MenuButtonAction::Display => menu_state.set(MenuState::Display),
+                    MenuButtonAction::Audio => menu_state.set(MenuState::Audio),
+                    MenuButtonAction::Controls => menu_state.set(MenuState::Controls),
+                    MenuButtonAction::Gameplay => menu_state.set(MenuState::Gameplay),
+                    MenuButtonAction::Rebind(binding) => rebinding.0 = Some(*binding),
+                    MenuButtonAction::ResetControls => {
+                        bindings.reset();
+                        bindings.save();
                     }
-                    MenuButtonAction::PlayTwo => {
-                        game_state.set(GameState::GameTwo);
-                        menu_state.set(MenuState::Disabled);
-                    }
-                    MenuButtonAction::PlayThree => {
-                        game_state.set(GameState::GameThree);
+                    MenuButtonAction::ToggleMute => audio_settings.muted = !audio_settings.muted,
+                    MenuButtonAction::BackToSettings => menu_state.set(MenuState::Settings),
+                    MenuButtonAction::BackToMainMenu => menu_state.set(MenuState::Main),
+                    // ENTERS GAMES
+                    MenuButtonAction::Play(state) => {
+                        game_state.set(*state);
                         menu_state.set(MenuState::Disabled);
                     }
-                    MenuButtonAction::PlayFour => {
-                        game_state.set(GameState::GameFour);
-                        menu_state.set(MenuState::Disabled);
+                    // Replays of games that are no longer registered can't be watched
+                    MenuButtonAction::WatchReplay(index) => {
+                        let Some(replay) = replay_list.as_ref().and_then(|list| list.0.get(*index)) else {
+                            continue;
+                        };
+                        if let Some(game) = registry.by_id(&replay.game) {
+                            commands.insert_resource(Playback::new(replay.clone()));
+                            game_state.set(game.state());
+                            menu_state.set(MenuState::Disabled);
+                        }
                     }
                 }
             }
         }
     }
 }
-
-// Generic system that takes a component as a parameter, and will despawn all entities with that component
-fn despawn_screen<T: Component>(to_despawn: Query<Entity, With<T>>, mut commands: Commands) {
-    for entity in &to_despawn {
-        commands.entity(entity).despawn_recursive();
-    }
-}
\ No newline at end of file
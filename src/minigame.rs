@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+
+use crate::controls::Action;
+use crate::difficulty::pick_run_difficulty;
+use crate::rng::reseed_rng;
+use crate::GameState;
+
+// A game that can be hosted by the launcher. The menu builds one button per registered game,
+// so adding a new game only needs an implementation of this trait and a `register_minigame` call.
+pub trait Minigame: Send + Sync + 'static {
+    // Title shown on the menu button
+    fn name(&self) -> &'static str;
+    // One line shown under the title
+    fn description(&self) -> &'static str;
+    // Asset path of the icon shown on the menu button
+    fn icon(&self) -> &'static str;
+    // State the game runs under
+    fn state(&self) -> GameState;
+    // Actions the game reads from `ButtonInput<Action>` and their default keys. `Action::Pause` is
+    // added to every game and shouldn't be listed.
+    fn controls(&self) -> &'static [(Action, KeyCode)];
+    // Asset path of the music looped while the game runs
+    fn music(&self) -> Option<&'static str> {
+        None
+    }
+    // Whether the assets a run can't start without have finished loading. Headless runs wait for
+    // this, so a run never depends on how fast the disk is.
+    fn is_loaded(&self, _world: &World) -> bool {
+        true
+    }
+    // Adds the game's systems, they should only run while `state()` is active
+    fn build(&self, app: &mut App);
+}
+
+// Every game registered with the app, in registration order
+#[derive(Resource, Default)]
+pub struct MinigameRegistry {
+    games: Vec<Box<dyn Minigame>>,
+}
+
+impl MinigameRegistry {
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Minigame> {
+        self.games.iter().map(|game| game.as_ref())
+    }
+
+    pub fn get(&self, state: GameState) -> Option<&dyn Minigame> {
+        self.iter().find(|game| game.state() == state)
+    }
+
+    // Looks a game up by the id it runs under in `GameState::Playing`
+    pub fn by_id(&self, id: &str) -> Option<&dyn Minigame> {
+        self.iter()
+            .find(|game| matches!(game.state(), GameState::Playing(game_id) if game_id == id))
+    }
+}
+
+pub trait RegisterMinigame {
+    fn register_minigame(&mut self, game: impl Minigame) -> &mut Self;
+}
+
+impl RegisterMinigame for App {
+    fn register_minigame(&mut self, game: impl Minigame) -> &mut Self {
+        self.add_systems(OnEnter(game.state()), (reseed_rng, pick_run_difficulty));
+        game.build(self);
+        self.world
+            .get_resource_or_insert_with(MinigameRegistry::default)
+            .games
+            .push(Box::new(game));
+        self
+    }
+}
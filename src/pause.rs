@@ -0,0 +1,107 @@
+// Pause overlay shared by every game. Pausing freezes `Time<Virtual>` and stops the `GameplaySet`,
+// so timers, spawners and movement all hold still until the game is resumed.
+
+use bevy::prelude::*;
+
+use crate::controls::Action;
+use crate::game_over::RunState;
+use crate::ui::{overlay_node, spawn_button, title_text};
+use crate::{despawn_screen, in_game, GameState, GameplaySet};
+
+#[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Hash, States)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
+}
+
+// Tag component used to tag entities added on the pause screen
+#[derive(Component)]
+struct OnPauseScreen;
+
+// All actions that can be triggered from a button click
+#[derive(Component)]
+enum PauseButtonAction {
+    Resume,
+    Restart,
+    QuitToMenu,
+}
+
+pub fn pause_plugin(app: &mut App) {
+    app.init_state::<PauseState>()
+        .configure_sets(Update, GameplaySet.run_if(in_state(PauseState::Running)))
+        .configure_sets(FixedUpdate, GameplaySet.run_if(in_state(PauseState::Running)))
+        .add_systems(OnEnter(PauseState::Paused), (pause_time, pause_setup))
+        .add_systems(OnExit(PauseState::Paused), (resume_time, despawn_screen::<OnPauseScreen>))
+        .add_systems(Update, toggle_pause.run_if(in_game))
+        .add_systems(Update, resume_on_game_change)
+        .add_systems(Update, pause_action.run_if(in_state(PauseState::Paused)));
+}
+
+// The pause action (P or Start on any gamepad by default) toggles the pause screen
+fn toggle_pause(
+    actions: Res<ButtonInput<Action>>,
+    run_state: Res<State<RunState>>,
+    pause_state: Res<State<PauseState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    if *run_state.get() == RunState::GameOver || !actions.just_pressed(Action::Pause) {
+        return;
+    }
+    next_pause_state.set(match pause_state.get() {
+        PauseState::Running => PauseState::Paused,
+        PauseState::Paused => PauseState::Running,
+    });
+}
+
+// Leaving or restarting a game never leaves the next one paused
+fn resume_on_game_change(
+    mut transitions: EventReader<StateTransitionEvent<GameState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    if !transitions.is_empty() {
+        transitions.clear();
+        next_pause_state.set(PauseState::Running);
+    }
+}
+
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+fn pause_setup(mut commands: Commands) {
+    commands
+        .spawn((overlay_node(), OnPauseScreen))
+        .with_children(|parent| {
+            parent.spawn(title_text("Paused"));
+            spawn_button(parent, "Resume", PauseButtonAction::Resume);
+            spawn_button(parent, "Restart", PauseButtonAction::Restart);
+            spawn_button(parent, "Quit to Menu", PauseButtonAction::QuitToMenu);
+        });
+}
+
+fn pause_action(
+    interaction_query: Query<(&Interaction, &PauseButtonAction), (Changed<Interaction>, With<Button>)>,
+    game_state: Res<State<GameState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    for (interaction, action) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match action {
+            PauseButtonAction::Resume => next_pause_state.set(PauseState::Running),
+            PauseButtonAction::Restart => {
+                if let GameState::Playing(id) = *game_state.get() {
+                    next_game_state.set(GameState::Restarting(id));
+                }
+            }
+            PauseButtonAction::QuitToMenu => next_game_state.set(GameState::Menu),
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+
+use crate::hud::Health;
+
+// Ferris, the crab controlled by the player
+#[derive(Component)]
+pub struct Player;
+
+pub fn player_plugin(app: &mut App) {
+    app.add_systems(Update, flip_on_death.run_if(resource_exists::<Health>));
+}
+
+// when the player runs out of health, flip Ferris upside down
+fn flip_on_death(health: Res<Health>, mut player_query: Query<&mut Transform, With<Player>>) {
+    if health.is_dead() {
+        for mut transform in &mut player_query {
+            transform.rotation = Quat::from_rotation_z(std::f32::consts::PI); // Rotate 180 degrees;
+        }
+    }
+}
@@ -0,0 +1,88 @@
+// Every game is laid out on the same fixed playfield, `PLAYFIELD` units centered on the origin. The
+// camera scales it to fit the window and letterboxes whatever is left over, so no game needs to
+// know how big the window is: bounds, spawn points and cleanup lines all come from `PLAYFIELD`.
+
+use bevy::prelude::*;
+use bevy::render::camera::{ScalingMode, Viewport};
+use bevy::window::PrimaryWindow;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Playfield {
+    pub width: f32,
+    pub height: f32,
+}
+
+pub const PLAYFIELD: Playfield = Playfield { width: 1280.0, height: 720.0 };
+
+impl Playfield {
+    pub fn size(&self) -> Vec2 {
+        Vec2::new(self.width, self.height)
+    }
+
+    pub fn left(&self) -> f32 {
+        -self.width / 2.0
+    }
+
+    pub fn right(&self) -> f32 {
+        self.width / 2.0
+    }
+
+    pub fn top(&self) -> f32 {
+        self.height / 2.0
+    }
+
+    pub fn bottom(&self) -> f32 {
+        -self.height / 2.0
+    }
+
+    // Whether `position` is more than `margin` past any of the edges
+    pub fn is_outside(&self, position: Vec2, margin: f32) -> bool {
+        position.x < self.left() - margin
+            || position.x > self.right() + margin
+            || position.y < self.bottom() - margin
+            || position.y > self.top() + margin
+    }
+}
+
+// Used by the apps with a window, keeps the playfield fitted to it
+pub fn playfield_plugin(app: &mut App) {
+    app.add_systems(Update, letterbox);
+}
+
+// The 2D camera every app uses, it always shows exactly the playfield
+pub fn spawn_camera(mut commands: Commands) {
+    let mut camera = Camera2dBundle::default();
+    camera.projection.scaling_mode = ScalingMode::Fixed {
+        width: PLAYFIELD.width,
+        height: PLAYFIELD.height,
+    };
+    commands.spawn(camera);
+}
+
+// Shrinks the camera's viewport to the biggest area with the playfield's aspect ratio and centers
+// it, and scales the UI along with it so HUDs and menus keep their layout
+fn letterbox(
+    windows: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+    mut cameras: Query<&mut Camera>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let window_size = Vec2::new(window.physical_width() as f32, window.physical_height() as f32);
+    // minimized
+    if window_size.min_element() < 1.0 {
+        return;
+    }
+    let scale = (window_size / PLAYFIELD.size()).min_element();
+    let viewport_size = (PLAYFIELD.size() * scale).floor().max(Vec2::ONE);
+    let viewport = Viewport {
+        physical_position: ((window_size - viewport_size) / 2.0).floor().as_uvec2(),
+        physical_size: viewport_size.as_uvec2(),
+        ..default()
+    };
+    for mut camera in &mut cameras {
+        camera.viewport = Some(viewport.clone());
+    }
+    ui_scale.0 = scale / window.scale_factor();
+}
@@ -0,0 +1,312 @@
+// Every run is recorded as the actions held on each frame, along with the run's seed. Games advance
+// by a fixed `FRAME_TIME` every frame, so feeding the same actions back in plays the exact same run
+// again, whatever keys or gamepad they came from.
+// Finished runs are kept in the data directory and can be watched from the launcher at 1x, 2x or 4x.
+
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::{PresentMode, PrimaryWindow};
+use serde::{Deserialize, Serialize};
+
+use crate::controls::{set_held, Action, ActionInputSet};
+use crate::difficulty::{Difficulty, RunDifficulty};
+use crate::game_over::RunState;
+use crate::high_scores::{save_high_scores, HighScores};
+use crate::hud::Score;
+use crate::pause::PauseState;
+use crate::rng::GameRng;
+use crate::storage::{data_dir, load_ron, save_ron};
+use crate::{in_game, GameState};
+
+// Game time that passes with every frame
+pub const FRAME_TIME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+const REPLAYS_DIR: &str = "replays";
+// Older replays are deleted once there are more than this
+const MAX_REPLAYS: usize = 20;
+
+// A stretch of frames that all had the same input, which keeps replay files small
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+struct InputSpan {
+    frames: u32,
+    // actions held down, sorted
+    actions: Vec<Action>,
+    paused: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Replay {
+    // id used in `GameState::Playing`
+    pub game: String,
+    pub seed: u64,
+    // replays from before difficulties were added were played at Normal
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    // seconds since the unix epoch
+    pub date: u64,
+    pub final_score: i32,
+    spans: Vec<InputSpan>,
+}
+
+impl Replay {
+    pub fn frame_count(&self) -> usize {
+        self.spans.iter().map(|span| span.frames as usize).sum()
+    }
+
+    // Reads a replay file from anywhere, e.g. one attached to a bug report or kept next to a test
+    pub fn load(path: impl AsRef<Path>) -> Option<Self> {
+        let text = fs::read_to_string(path).ok()?;
+        ron::from_str(&text).ok()
+    }
+
+    // Saved replays, newest first
+    pub fn load_all() -> Vec<Self> {
+        let Ok(files) = fs::read_dir(data_dir().join(REPLAYS_DIR)) else {
+            return Vec::new();
+        };
+        let mut replays: Vec<Self> = files
+            .filter_map(|file| file.ok())
+            .filter_map(|file| load_ron(&format!("{REPLAYS_DIR}/{}", file.file_name().to_string_lossy())))
+            .collect();
+        replays.sort_by_key(|replay| std::cmp::Reverse(replay.date));
+        replays
+    }
+
+    pub fn save(&self) {
+        save_ron(&self.file_name(), self);
+        for old in Self::load_all().iter().skip(MAX_REPLAYS) {
+            let _ = fs::remove_file(data_dir().join(old.file_name()));
+        }
+    }
+
+    fn file_name(&self) -> String {
+        format!("{REPLAYS_DIR}/{}-{}-{}.ron", self.game, self.date, self.seed)
+    }
+}
+
+// Input of the run being played, turned into a `Replay` when the run ends
+#[derive(Resource, Default)]
+struct Recording {
+    spans: Vec<InputSpan>,
+}
+
+impl Recording {
+    fn push_frame(&mut self, actions: Vec<Action>, paused: bool) {
+        match self.spans.last_mut() {
+            Some(span) if span.actions == actions && span.paused == paused => span.frames += 1,
+            _ => self.spans.push(InputSpan { frames: 1, actions, paused }),
+        }
+    }
+}
+
+// The most recently finished run
+#[derive(Resource, Default)]
+pub struct LastReplay(pub Option<Replay>);
+
+// Present while a replay is being watched, the recorded actions replace the live ones
+#[derive(Resource)]
+pub struct Playback {
+    replay: Replay,
+    // position in `replay.spans`
+    span: usize,
+    frame_in_span: u32,
+}
+
+impl Playback {
+    // Start watching by inserting this and switching to the replay's `GameState::Playing`
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            span: 0,
+            frame_in_span: 0,
+        }
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    fn rewind(&mut self) {
+        self.span = 0;
+        self.frame_in_span = 0;
+    }
+
+    fn next_frame(&mut self) -> Option<InputSpan> {
+        let span = self.replay.spans.get(self.span)?.clone();
+        self.frame_in_span += 1;
+        if self.frame_in_span >= span.frames {
+            self.span += 1;
+            self.frame_in_span = 0;
+        }
+        Some(span)
+    }
+}
+
+// How fast replays are played back, picked on the launcher's replay screen
+#[derive(Resource, Component, Clone, Copy, PartialEq, Debug)]
+pub struct ReplaySpeed(pub u32);
+
+impl Default for ReplaySpeed {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+pub fn replay_plugin(app: &mut App) {
+    app.init_resource::<Recording>()
+        .init_resource::<LastReplay>()
+        .init_resource::<ReplaySpeed>()
+        .add_systems(PreUpdate, apply_playback.after(ActionInputSet).run_if(resource_exists::<Playback>))
+        .add_systems(Update, follow_game_change)
+        .add_systems(
+            OnEnter(RunState::GameOver),
+            finish_recording.run_if(not(resource_exists::<Playback>)),
+        )
+        .add_systems(
+            Last,
+            record_frame
+                .run_if(in_game)
+                .run_if(in_state(RunState::Playing))
+                .run_if(not(resource_exists::<Playback>)),
+        );
+}
+
+// Used by the apps with a window: keeps the fixed-step games at real time speed and saves every
+// finished run and new high score. Headless runs leave this out, so they run as fast as possible
+// and don't write to the data directory.
+pub fn realtime_plugin(app: &mut App) {
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(FRAME_TIME))
+        .add_systems(Update, save_last_replay.run_if(resource_changed::<LastReplay>))
+        // not when the saved scores are first loaded
+        .add_systems(
+            Update,
+            save_high_scores.run_if(resource_changed::<HighScores>.and_then(not(resource_added::<HighScores>))),
+        )
+        .add_systems(Update, match_present_mode)
+        .add_systems(Last, limit_frame_rate);
+}
+
+fn record_frame(
+    actions: Res<ButtonInput<Action>>,
+    pause_state: Res<State<PauseState>>,
+    mut recording: ResMut<Recording>,
+) {
+    let mut held: Vec<Action> = actions.get_pressed().copied().collect();
+    held.sort();
+    recording.push_frame(held, *pause_state.get() == PauseState::Paused);
+}
+
+fn finish_recording(
+    game_state: Res<State<GameState>>,
+    rng: Res<GameRng>,
+    difficulty: Res<RunDifficulty>,
+    score: Option<Res<Score>>,
+    mut recording: ResMut<Recording>,
+    mut last_replay: ResMut<LastReplay>,
+) {
+    let GameState::Playing(game) = *game_state.get() else {
+        return;
+    };
+    let date = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    last_replay.0 = Some(Replay {
+        game: game.to_string(),
+        seed: rng.seed(),
+        difficulty: difficulty.0,
+        date,
+        final_score: score.map_or(0, |score| score.score),
+        spans: std::mem::take(&mut recording.spans),
+    });
+}
+
+// Every run starts a new recording. A restarted replay is watched again from the start, leaving
+// the game stops watching.
+fn follow_game_change(
+    mut commands: Commands,
+    mut transitions: EventReader<StateTransitionEvent<GameState>>,
+    mut recording: ResMut<Recording>,
+    mut playback: Option<ResMut<Playback>>,
+) {
+    for transition in transitions.read() {
+        recording.spans.clear();
+        let Some(playback) = playback.as_mut() else {
+            continue;
+        };
+        match transition.after {
+            GameState::Restarting(id) if id == playback.replay.game => playback.rewind(),
+            GameState::Playing(id) if id == playback.replay.game => {}
+            _ => commands.remove_resource::<Playback>(),
+        }
+    }
+}
+
+// Runs right after the live actions are read and replaces them, Escape still leaves the replay
+fn apply_playback(
+    mut playback: ResMut<Playback>,
+    mut actions: ResMut<ButtonInput<Action>>,
+    pause_state: Res<State<PauseState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    // past the end the recorded run is over, nothing is held any more
+    let Some(frame) = playback.next_frame() else {
+        set_held(&mut actions, &[]);
+        return;
+    };
+    set_held(&mut actions, &frame.actions);
+
+    let paused = if frame.paused { PauseState::Paused } else { PauseState::Running };
+    if *pause_state.get() != paused {
+        next_pause_state.set(paused);
+    }
+}
+
+fn save_last_replay(last_replay: Res<LastReplay>) {
+    if let Some(replay) = &last_replay.0 {
+        replay.save();
+    }
+}
+
+// The games move a fixed amount every frame, so a frame must not take less than `FRAME_TIME` of real
+// time, or the games would run too fast on high refresh rate screens. Replays divide it by their speed.
+fn limit_frame_rate(
+    mut last_frame: Local<Option<Instant>>,
+    playback: Option<Res<Playback>>,
+    speed: Res<ReplaySpeed>,
+) {
+    let speed = if playback.is_some() { speed.0 } else { 1 };
+    let frame_time = FRAME_TIME / speed;
+    if let Some(last_frame) = *last_frame {
+        let elapsed = last_frame.elapsed();
+        if elapsed < frame_time {
+            thread::sleep(frame_time - elapsed);
+        }
+    }
+    *last_frame = Some(Instant::now());
+}
+
+// Vsync would hold fast replays back to the screen's refresh rate
+fn match_present_mode(
+    playback: Option<Res<Playback>>,
+    speed: Res<ReplaySpeed>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    mut vsync_mode: Local<Option<PresentMode>>,
+) {
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+    let fast = playback.is_some() && speed.0 > 1;
+    if fast && vsync_mode.is_none() {
+        *vsync_mode = Some(window.present_mode);
+        window.present_mode = PresentMode::AutoNoVsync;
+    } else if !fast {
+        if let Some(mode) = vsync_mode.take() {
+            window.present_mode = mode;
+        }
+    }
+}
@@ -0,0 +1,80 @@
+// Every random choice the games make (enemy positions, falling food, rock speeds) is drawn from
+// `GameRng`, which is reseeded at the start of every run. The seed is printed when a run starts
+// and shown on the game over screen; starting with `--seed <n>` plays that exact run again.
+
+use std::ops::{Deref, DerefMut};
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::replay::Playback;
+use crate::GameState;
+
+// Seed given with `--seed` (see `cli`), used for every run instead of a fresh random one
+#[derive(Resource, Clone, Copy, Default)]
+pub struct FixedSeed(pub Option<u64>);
+
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    // Seed the current run started from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::from_seed(rand::random())
+    }
+}
+
+impl Deref for GameRng {
+    type Target = StdRng;
+
+    fn deref(&self) -> &StdRng {
+        &self.rng
+    }
+}
+
+impl DerefMut for GameRng {
+    fn deref_mut(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+}
+
+pub fn rng_plugin(app: &mut App) {
+    app.init_resource::<FixedSeed>().init_resource::<GameRng>();
+}
+
+// Added to every game's `OnEnter` by `register_minigame`. Setup systems that draw from the rng
+// should run `.after(reseed_rng)`.
+pub fn reseed_rng(
+    fixed_seed: Res<FixedSeed>,
+    playback: Option<Res<Playback>>,
+    game_state: Res<State<GameState>>,
+    mut rng: ResMut<GameRng>,
+) {
+    // a replay has to use the seed it was recorded with
+    let seed = playback
+        .map(|playback| playback.replay().seed)
+        .or(fixed_seed.0)
+        .unwrap_or_else(rand::random);
+    *rng = GameRng::from_seed(seed);
+    // on stderr, so stdout only has what `--headless` prints
+    if let GameState::Playing(id) = game_state.get() {
+        eprintln!("{id}: seed {seed} (run again with --seed {seed})");
+    }
+}
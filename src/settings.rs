@@ -0,0 +1,179 @@
+// Display and gameplay settings picked on the launcher's Settings screen. Each setting is its own
+// resource so the menu's option buttons can set it directly, they are saved together in one file
+// and applied to the window as soon as they change. Volumes and key bindings keep their own files.
+
+use bevy::prelude::*;
+use bevy::window::{PresentMode, PrimaryWindow, WindowMode};
+use serde::{Deserialize, Serialize};
+
+use crate::difficulty::Difficulty;
+use crate::storage::{load_ron, save_ron};
+
+const SETTINGS_FILE: &str = "settings.ron";
+
+#[derive(Resource, Component, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum DisplayMode {
+    #[default]
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+impl DisplayMode {
+    pub const ALL: [DisplayMode; 3] = [DisplayMode::Windowed, DisplayMode::Borderless, DisplayMode::Fullscreen];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DisplayMode::Windowed => "Windowed",
+            DisplayMode::Borderless => "Borderless",
+            DisplayMode::Fullscreen => "Fullscreen",
+        }
+    }
+
+    fn window_mode(self) -> WindowMode {
+        match self {
+            DisplayMode::Windowed => WindowMode::Windowed,
+            DisplayMode::Borderless => WindowMode::BorderlessFullscreen,
+            DisplayMode::Fullscreen => WindowMode::Fullscreen,
+        }
+    }
+}
+
+#[derive(Resource, Component, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum Vsync {
+    #[default]
+    On,
+    Off,
+}
+
+impl Vsync {
+    pub const ALL: [Vsync; 2] = [Vsync::On, Vsync::Off];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Vsync::On => "On",
+            Vsync::Off => "Off",
+        }
+    }
+
+    fn present_mode(self) -> PresentMode {
+        match self {
+            Vsync::On => PresentMode::AutoVsync,
+            Vsync::Off => PresentMode::AutoNoVsync,
+        }
+    }
+}
+
+// Size of the window in windowed mode, the playfield is scaled to fit whatever it is
+#[derive(Resource, Component, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Resolution(pub u32, pub u32);
+
+impl Default for Resolution {
+    fn default() -> Self {
+        Self(1280, 720)
+    }
+}
+
+impl Resolution {
+    pub const ALL: [Resolution; 3] = [Resolution(1280, 720), Resolution(1600, 900), Resolution(1920, 1080)];
+
+    pub fn label(self) -> String {
+        format!("{}x{}", self.0, self.1)
+    }
+}
+
+// What's saved to the settings file
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Debug)]
+#[serde(default)]
+pub struct Settings {
+    pub display_mode: DisplayMode,
+    pub vsync: Vsync,
+    pub resolution: Resolution,
+    pub difficulty: Difficulty,
+}
+
+// Settings given on the command line, they only last for the run
+#[derive(Resource, Clone, Copy, Default, PartialEq, Debug)]
+pub struct SettingsOverrides {
+    pub display_mode: Option<DisplayMode>,
+    pub resolution: Option<Resolution>,
+    pub difficulty: Option<Difficulty>,
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        load_ron(SETTINGS_FILE).unwrap_or_default()
+    }
+
+    // The settings in effect right now
+    pub fn current(world: &World) -> Self {
+        Self {
+            display_mode: *world.resource::<DisplayMode>(),
+            vsync: *world.resource::<Vsync>(),
+            resolution: *world.resource::<Resolution>(),
+            difficulty: *world.resource::<Difficulty>(),
+        }
+    }
+
+    // What to save instead of these settings: the ones still at their command line override keep
+    // the `saved` value, the ones picked since then are kept
+    pub fn without_overrides(self, saved: Settings, overrides: &SettingsOverrides) -> Self {
+        fn keep<T: PartialEq + Copy>(current: T, saved: T, overridden: Option<T>) -> T {
+            if overridden == Some(current) { saved } else { current }
+        }
+        Self {
+            display_mode: keep(self.display_mode, saved.display_mode, overrides.display_mode),
+            vsync: self.vsync,
+            resolution: keep(self.resolution, saved.resolution, overrides.resolution),
+            difficulty: keep(self.difficulty, saved.difficulty, overrides.difficulty),
+        }
+    }
+
+    pub fn save(&self) {
+        save_ron(SETTINGS_FILE, self);
+    }
+}
+
+// Used by the apps with a window. Replaces the default `Difficulty` with the saved one.
+pub fn settings_plugin(app: &mut App) {
+    let settings = Settings::load();
+    app.insert_resource(settings.display_mode)
+        .insert_resource(settings.vsync)
+        .insert_resource(settings.resolution)
+        .insert_resource(settings.difficulty)
+        .init_resource::<SettingsOverrides>()
+        .add_systems(
+            Update,
+            (
+                apply_display_mode.run_if(resource_changed::<DisplayMode>),
+                apply_vsync.run_if(resource_changed::<Vsync>),
+                apply_resolution.run_if(resource_changed::<Resolution>),
+            ),
+        );
+}
+
+// Run by the launcher when a settings page is closed
+pub fn save_settings(world: &World) {
+    let overrides = world.get_resource::<SettingsOverrides>().copied().unwrap_or_default();
+    Settings::current(world).without_overrides(Settings::load(), &overrides).save();
+}
+
+fn apply_display_mode(display_mode: Res<DisplayMode>, mut windows: Query<&mut Window, With<PrimaryWindow>>) {
+    for mut window in &mut windows {
+        window.mode = display_mode.window_mode();
+    }
+}
+
+fn apply_vsync(vsync: Res<Vsync>, mut windows: Query<&mut Window, With<PrimaryWindow>>) {
+    for mut window in &mut windows {
+        window.present_mode = vsync.present_mode();
+    }
+}
+
+// Only applied on its own resolution changing, so switching vsync doesn't undo a window the player
+// resized by hand
+fn apply_resolution(resolution: Res<Resolution>, mut windows: Query<&mut Window, With<PrimaryWindow>>) {
+    for mut window in &mut windows {
+        window.resolution.set(resolution.0 as f32, resolution.1 as f32);
+    }
+}
@@ -0,0 +1,41 @@
+// Files kept between runs (high scores, settings, replays) live in the user's data directory
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+// e.g. ~/.local/share/cRUSTyCrab on Linux, falls back to the working directory
+pub fn data_dir() -> PathBuf {
+    dirs::data_dir()
+        .map(|dir| dir.join("cRUSTyCrab"))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+// Reads a RON file from the data directory, `None` if it is missing or can't be parsed
+pub fn load_ron<T: DeserializeOwned>(file_name: &str) -> Option<T> {
+    let text = fs::read_to_string(data_dir().join(file_name)).ok()?;
+    match ron::from_str(&text) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            eprintln!("ignoring unreadable {file_name}: {err}");
+            None
+        }
+    }
+}
+
+// Writes a RON file to the data directory, creating any missing folders. Errors are reported but
+// never stop the game
+pub fn save_ron<T: Serialize>(file_name: &str, value: &T) {
+    let path = data_dir().join(file_name);
+    let result = fs::create_dir_all(path.parent().unwrap_or(&path))
+        .map_err(|err| err.to_string())
+        .and_then(|_| {
+            ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+                .map_err(|err| err.to_string())
+        })
+        .and_then(|text| fs::write(&path, text).map_err(|err| err.to_string()));
+    if let Err(err) = result {
+        eprintln!("could not save {file_name}: {err}");
+    }
+}
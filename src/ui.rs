@@ -0,0 +1,99 @@
+// Button styling shared by the launcher menu and the in-game overlays
+
+use bevy::prelude::*;
+
+pub const TEXT_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
+
+pub const NORMAL_BUTTON: Color = Color::rgb(0.15, 0.15, 0.15);
+pub const HOVERED_BUTTON: Color = Color::rgb(0.25, 0.25, 0.25);
+pub const HOVERED_PRESSED_BUTTON: Color = Color::rgb(0.25, 0.65, 0.25);
+pub const PRESSED_BUTTON: Color = Color::rgb(0.35, 0.75, 0.35);
+
+// Tag component used to mark which setting is currently selected
+#[derive(Component)]
+pub struct SelectedOption;
+
+pub fn ui_plugin(app: &mut App) {
+    app.add_systems(Update, button_system);
+}
+
+// This system handles changing all buttons color based on mouse interaction
+fn button_system(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor, Option<&SelectedOption>),
+        (Changed<Interaction>, With<Button>),
+    >,
+) {
+    for (interaction, mut color, selected) in &mut interaction_query {
+        *color = match (*interaction, selected) {
+            (Interaction::Pressed, _) | (Interaction::None, Some(_)) => PRESSED_BUTTON.into(),
+            (Interaction::Hovered, Some(_)) => HOVERED_PRESSED_BUTTON.into(),
+            (Interaction::Hovered, None) => HOVERED_BUTTON.into(),
+            (Interaction::None, None) => NORMAL_BUTTON.into(),
+        }
+    }
+}
+
+// Full screen node that dims the game behind an overlay and centers its content in a column
+pub fn overlay_node() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            position_type: PositionType::Absolute,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+        // draw above the game's own HUD
+        z_index: ZIndex::Global(10),
+        ..default()
+    }
+}
+
+pub fn title_text(title: &str) -> TextBundle {
+    TextBundle::from_section(
+        title,
+        TextStyle {
+            font_size: 80.0,
+            color: TEXT_COLOR,
+            ..default()
+        },
+    )
+    .with_style(Style {
+        margin: UiRect::all(Val::Px(30.0)),
+        ..default()
+    })
+}
+
+// A text button in the same style as the main menu, tagged with its action
+pub fn spawn_button(parent: &mut ChildBuilder, label: &str, action: impl Component) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(275.0),
+                    height: Val::Px(65.0),
+                    margin: UiRect::all(Val::Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: NORMAL_BUTTON.into(),
+                ..default()
+            },
+            action,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font_size: 33.0,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            ));
+        });
+}
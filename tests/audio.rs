@@ -0,0 +1,23 @@
+use crusty_crab::audio::AudioSettings;
+
+#[test]
+fn volumes_are_scaled_by_the_master_volume() {
+    let settings = AudioSettings {
+        master: 0.5,
+        music: 0.6,
+        sfx: 1.0,
+        muted: false,
+    };
+    assert!((settings.music_volume() - 0.3).abs() < 1e-6);
+    assert!((settings.sfx_volume() - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn muting_silences_everything() {
+    let settings = AudioSettings {
+        muted: true,
+        ..Default::default()
+    };
+    assert_eq!(settings.music_volume(), 0.0);
+    assert_eq!(settings.sfx_volume(), 0.0);
+}
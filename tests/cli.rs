@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+use crusty_crab::cli::{run_headless, CliArgs};
+use crusty_crab::crabshooter::CrabShooter;
+use crusty_crab::difficulty::Difficulty;
+use crusty_crab::gametwo::SeafoodScramble;
+use crusty_crab::minigame::RegisterMinigame;
+use crusty_crab::settings::{DisplayMode, Resolution, Settings, SettingsOverrides, Vsync};
+
+fn parse(args: &[&str]) -> Result<CliArgs, String> {
+    CliArgs::parse(args.iter().map(|arg| arg.to_string()))
+}
+
+fn register_games(app: &mut App) {
+    app.register_minigame(SeafoodScramble).register_minigame(CrabShooter);
+}
+
+#[test]
+fn options_take_their_value_either_way() {
+    let args = parse(&["--game", "shooter", "--seed=42", "--difficulty", "HARD", "--size=1600x900", "--fullscreen"]).unwrap();
+    assert_eq!(args.game.as_deref(), Some("shooter"));
+    assert_eq!(args.seed, Some(42));
+    assert_eq!(args.difficulty, Some(Difficulty::Hard));
+    assert_eq!(args.size, Some(Resolution(1600, 900)));
+    assert!(args.fullscreen);
+    assert!(!args.debug_physics);
+}
+
+#[test]
+fn bad_options_are_errors() {
+    assert!(parse(&["--speed", "2"]).is_err());
+    assert!(parse(&["--seed", "abc"]).is_err());
+    assert!(parse(&["--seed"]).is_err());
+    assert!(parse(&["--size", "1600"]).is_err());
+    assert!(parse(&["--difficulty", "nightmare"]).is_err());
+    assert!(parse(&["--game", "shooter", "--replay", "run.ron"]).is_err());
+}
+
+#[test]
+fn headless_runs_print_the_result_as_json() {
+    let args = parse(&["--game", "shooter", "--seed", "7", "--headless", "60"]).unwrap();
+    let result = run_headless(&args, register_games).unwrap();
+    assert_eq!(
+        result,
+        r#"{"game":"crabshooter","seed":7,"difficulty":"Normal","frames":60,"score":0,"health":3,"game_over":false}"#
+    );
+}
+
+#[test]
+fn headless_runs_need_a_game_they_know() {
+    let no_game = parse(&["--headless", "60"]).unwrap();
+    assert!(run_headless(&no_game, register_games).is_err());
+    let unknown = parse(&["--game", "pong", "--headless", "60"]).unwrap();
+    assert!(run_headless(&unknown, register_games).is_err());
+}
+
+#[test]
+fn command_line_settings_are_not_saved() {
+    let saved = Settings::default();
+    let mut app = App::new();
+    app.insert_resource(saved.display_mode)
+        .insert_resource(saved.vsync)
+        .insert_resource(saved.resolution)
+        .insert_resource(saved.difficulty);
+    parse(&["--fullscreen", "--size", "1000x800", "--difficulty", "hard"]).unwrap().apply(&mut app);
+    assert_eq!(*app.world.resource::<DisplayMode>(), DisplayMode::Fullscreen);
+    assert_eq!(*app.world.resource::<Resolution>(), Resolution(1000, 800));
+
+    // opening and closing a settings page saves the file as it was
+    let overrides = *app.world.resource::<SettingsOverrides>();
+    assert_eq!(Settings::current(&app.world).without_overrides(saved, &overrides), saved);
+
+    // but what's picked in the menu afterwards is saved
+    app.insert_resource(Resolution(1920, 1080)).insert_resource(Vsync::Off);
+    let to_save = Settings::current(&app.world).without_overrides(saved, &overrides);
+    assert_eq!(to_save.resolution, Resolution(1920, 1080));
+    assert_eq!(to_save.vsync, Vsync::Off);
+    assert_eq!(to_save.display_mode, saved.display_mode);
+    assert_eq!(to_save.difficulty, saved.difficulty);
+}
@@ -0,0 +1,577 @@
+use bevy::prelude::*;
+use crusty_crab::audio::{PlaySfx, Sfx};
+use crusty_crab::crabshooter::{
+    Boss, BossPhase, Charged, CrabShooter, Dive, Enemy, EnemyGun, EnemyKind, EnemyProjectile, FirePattern, Formation,
+    Homing, InFormation, PickUp, Piercing, PlayerShield, Projectile, ProjectileVelocity, Shot, Strafe, WavePhase, Waves,
+    Weapon, CHARGED_DAMAGE,
+};
+use crusty_crab::headless::HeadlessGame;
+use crusty_crab::hud::Score;
+use crusty_crab::player::Player;
+use crusty_crab::playfield::PLAYFIELD;
+
+fn player_position(game: &mut HeadlessGame) -> Vec3 {
+    game.world()
+        .query_filtered::<&Transform, With<Player>>()
+        .single(game.world())
+        .translation
+}
+
+#[test]
+fn three_enemy_projectiles_kill_the_player() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.step();
+    assert_eq!(game.health(), Some(3));
+
+    let position = player_position(&mut game);
+    for _ in 0..3 {
+        game.world().spawn((Transform::from_translation(position), EnemyProjectile));
+    }
+
+    game.run_frames(3);
+    assert_eq!(game.health(), Some(0));
+    assert!(game.is_game_over());
+}
+
+#[test]
+fn getting_hit_plays_a_sound() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.step();
+
+    let position = player_position(&mut game);
+    game.world().spawn((Transform::from_translation(position), EnemyProjectile));
+    game.step();
+
+    let sounds: Vec<Sfx> = game
+        .world()
+        .resource::<Events<PlaySfx>>()
+        .iter_current_update_events()
+        .map(|sfx| sfx.0)
+        .collect();
+    assert_eq!(sounds, vec![Sfx::PlayerHit]);
+}
+
+#[test]
+fn player_moves_with_the_arrow_keys() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.step();
+    let start = player_position(&mut game);
+
+    game.hold(KeyCode::ArrowLeft, 30);
+    assert!(player_position(&mut game).x < start.x);
+}
+
+// Where the enemies are every second, they move around and some fly off or crash into the player
+fn enemy_positions(seed: u64) -> Vec<Vec3> {
+    let mut game = HeadlessGame::with_seed(CrabShooter, seed);
+    let mut positions = Vec::new();
+    // long enough for a few enemies to spawn
+    for _ in 0..10 {
+        game.run_frames(60);
+        positions.extend(
+            game.world()
+                .query_filtered::<&Transform, With<Enemy>>()
+                .iter(game.world())
+                .map(|transform| transform.translation),
+        );
+    }
+    positions
+}
+
+#[test]
+fn the_same_seed_spawns_the_same_enemies() {
+    let enemies = enemy_positions(42);
+    // more than just the first enemy
+    assert!(enemies.len() > 10);
+    assert_eq!(enemies, enemy_positions(42));
+}
+
+#[test]
+fn strafers_turn_around_at_the_walls() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.step();
+    let start = Vec3::new(PLAYFIELD.right() - 50.0, 0.0, 1.0);
+    let strafer = game.world().spawn((Transform::from_translation(start), Enemy, Strafe { speed: 150.0 })).id();
+
+    game.run_frames(30);
+    let strafer = game.world().get::<Transform>(strafer).unwrap().translation;
+    assert!(strafer.x < start.x);
+}
+
+#[test]
+fn divers_fly_into_the_player() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.step();
+    let above_player = player_position(&mut game) + Vec3::new(50.0, 150.0, 0.0);
+    let diver = game.world().spawn((Transform::from_translation(above_player), Enemy, Dive::new(0.1))).id();
+
+    game.run_frames(40);
+    assert_eq!(game.health(), Some(2));
+    assert!(game.world().get_entity(diver).is_none());
+}
+
+#[test]
+fn formations_step_down_at_the_walls() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.step();
+    let formation = game
+        .world()
+        .spawn(Formation {
+            center: Vec2::new(PLAYFIELD.right() - 101.0, 0.0),
+            direction: 1.0,
+            half_width: 100.0,
+        })
+        .id();
+    let offset = Vec2::new(-50.0, 0.0);
+    let member = game
+        .world()
+        .spawn((Transform::default(), Enemy, InFormation { formation, offset }))
+        .id();
+
+    game.run_frames(5);
+    let position = game.world().get::<Transform>(member).unwrap().translation;
+    assert!(position.y < 0.0);
+    assert!(position.x < PLAYFIELD.right() - 151.0);
+
+    // the formation goes once it's empty
+    game.world().despawn(member);
+    game.run_frames(2);
+    assert!(game.world().get_entity(formation).is_none());
+}
+
+fn enemy_projectile_velocities(game: &mut HeadlessGame) -> Vec<Vec2> {
+    game.world()
+        .query_filtered::<&ProjectileVelocity, With<EnemyProjectile>>()
+        .iter(game.world())
+        .map(|velocity| velocity.0)
+        .collect()
+}
+
+fn spawn_gunner(game: &mut HeadlessGame, position: Vec3, gun: EnemyGun) {
+    game.world().spawn((Transform::from_translation(position), Enemy, gun));
+}
+
+#[test]
+fn every_enemy_fires_on_its_own() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.step();
+    spawn_gunner(&mut game, Vec3::new(-400.0, 200.0, 1.0), EnemyGun::new(FirePattern::Straight, 1.0));
+    spawn_gunner(&mut game, Vec3::new(400.0, 200.0, 1.0), EnemyGun::new(FirePattern::Straight, 1.0));
+
+    game.run_frames(120);
+    let shooters: Vec<f32> = game
+        .world()
+        .query_filtered::<&Transform, With<EnemyProjectile>>()
+        .iter(game.world())
+        .map(|transform| transform.translation.x)
+        .collect();
+    assert!(shooters.contains(&-400.0));
+    assert!(shooters.contains(&400.0));
+}
+
+#[test]
+fn spread_shots_fan_out() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.step();
+    let spread = FirePattern::Spread { shots: 3, angle: 40.0 };
+    spawn_gunner(&mut game, Vec3::new(-400.0, 200.0, 1.0), EnemyGun::new(spread, 0.5));
+
+    game.run_frames(60);
+    // leaving out the first Corro's shots
+    let velocities: Vec<Vec2> = game
+        .world()
+        .query_filtered::<(&Transform, &ProjectileVelocity), With<EnemyProjectile>>()
+        .iter(game.world())
+        .filter(|(transform, _)| transform.translation.x < -200.0)
+        .map(|(_, velocity)| velocity.0)
+        .collect();
+    assert_eq!(velocities.len(), 3);
+    assert!(velocities.iter().any(|velocity| velocity.x < 0.0));
+    assert!(velocities.iter().any(|velocity| velocity.x > 0.0));
+    assert!(velocities.iter().all(|velocity| velocity.y < 0.0));
+}
+
+#[test]
+fn aimed_shots_lead_a_moving_player() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.step();
+    game.press(KeyCode::ArrowRight);
+    game.step();
+    let above_player = player_position(&mut game) + Vec3::new(0.0, 400.0, 0.0);
+    spawn_gunner(&mut game, above_player, EnemyGun::new(FirePattern::Aimed, 0.5));
+
+    game.run_frames(60);
+    let velocities = enemy_projectile_velocities(&mut game);
+    // shooting at where the player is now would be almost straight down
+    assert!(velocities.iter().any(|velocity| velocity.x > velocity.length() / 2.0));
+}
+
+#[test]
+fn more_enemies_dont_fill_the_screen_with_shots() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.step();
+    for column in 0..30 {
+        let position = Vec3::new(-600.0 + column as f32 * 40.0, 200.0, 1.0);
+        spawn_gunner(&mut game, position, EnemyGun::new(FirePattern::Straight, 0.5));
+    }
+
+    // 30 enemies firing every half a second would be 60 shots
+    game.run_frames(60);
+    let shots = game
+        .world()
+        .query_filtered::<(), With<EnemyProjectile>>()
+        .iter(game.world())
+        .count();
+    assert!(shots <= 3, "{shots} shots in a second");
+}
+
+fn enemy_count(game: &mut HeadlessGame) -> usize {
+    game.world().query_filtered::<(), With<Enemy>>().iter(game.world()).count()
+}
+
+#[test]
+fn waves_start_with_a_banner() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.run_frames(60);
+    assert!(matches!(game.world().resource::<Waves>().phase, WavePhase::Banner(_)));
+    assert_eq!(enemy_count(&mut game), 0);
+
+    // the banner's gone after two seconds and the first wave comes in
+    game.run_frames(70);
+    assert!(matches!(game.world().resource::<Waves>().phase, WavePhase::Spawning { .. }));
+    assert!(enemy_count(&mut game) > 0);
+}
+
+#[test]
+fn clearing_a_wave_starts_the_next_one() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    // the first wave has three enemies a second and a half apart
+    game.run_frames(120 + 200);
+    assert_eq!(enemy_count(&mut game), 3);
+    assert_eq!(game.world().resource::<Waves>().wave, 0);
+
+    let enemies: Vec<Entity> = game.world().query_filtered::<Entity, With<Enemy>>().iter(game.world()).collect();
+    for enemy in enemies {
+        game.world().despawn(enemy);
+    }
+    game.run_frames(2);
+    let waves = game.world().resource::<Waves>();
+    assert_eq!(waves.wave, 1);
+    assert!(matches!(waves.phase, WavePhase::Banner(_)));
+}
+
+#[test]
+fn beating_the_last_wave_wins_the_run() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    // every enemy is gone as soon as it comes in
+    for _ in 0..6000 {
+        if game.is_game_over() {
+            break;
+        }
+        let enemies: Vec<Entity> = game
+            .world()
+            .query_filtered::<Entity, Or<(With<Enemy>, With<Boss>)>>()
+            .iter(game.world())
+            .collect();
+        for enemy in enemies {
+            game.world().entity_mut(enemy).despawn_recursive();
+        }
+        game.step();
+    }
+    assert!(matches!(game.world().resource::<Waves>().phase, WavePhase::Won));
+    assert!(game.is_game_over());
+    assert_eq!(game.health(), Some(3));
+}
+
+fn spawn_boss(game: &mut HeadlessGame, boss: Boss) -> Entity {
+    game.world().spawn((Transform::from_xyz(0.0, 150.0, 1.0), boss)).id()
+}
+
+fn boss_health(game: &mut HeadlessGame, boss: Entity) -> i32 {
+    game.world().get::<Boss>(boss).unwrap().health
+}
+
+fn shoot_at(game: &mut HeadlessGame, target: Vec2) {
+    game.world().spawn((Transform::from_translation(target.extend(1.0)), Projectile));
+}
+
+#[test]
+fn weak_points_take_bonus_damage() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.step();
+    let boss = spawn_boss(&mut game, Boss::new());
+    game.step();
+
+    let center = game.world().get::<Transform>(boss).unwrap().translation.truncate();
+    shoot_at(&mut game, center);
+    game.step();
+    let body_damage = Boss::MAX_HEALTH - boss_health(&mut game, boss);
+    assert_eq!(body_damage, 1);
+
+    let center = game.world().get::<Transform>(boss).unwrap().translation.truncate();
+    shoot_at(&mut game, center + Boss::WEAK_POINTS[0]);
+    game.step();
+    let weak_point_damage = Boss::MAX_HEALTH - body_damage - boss_health(&mut game, boss);
+    assert!(weak_point_damage > body_damage);
+}
+
+#[test]
+fn the_boss_attacks_harder_as_its_health_runs_out() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.step();
+    let mut boss = Boss::new();
+    assert_eq!(boss.phase(), BossPhase::Fan);
+    boss.health = Boss::MAX_HEALTH / 2;
+    assert_eq!(boss.phase(), BossPhase::Homing);
+    spawn_boss(&mut game, boss);
+
+    game.run_frames(125);
+    let homing = game.world().query_filtered::<(), (With<EnemyProjectile>, With<Homing>)>().iter(game.world()).count();
+    assert!(homing > 0);
+
+    let mut boss = Boss::new();
+    boss.health = 1;
+    assert_eq!(boss.phase(), BossPhase::Summon);
+}
+
+#[test]
+fn the_boss_summons_minions_when_almost_beaten() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.step();
+    let mut boss = Boss::new();
+    boss.health = 1;
+    spawn_boss(&mut game, boss);
+
+    game.run_frames(125);
+    // the first wave only has plain Corros
+    let divers = game
+        .world()
+        .query::<&EnemyKind>()
+        .iter(game.world())
+        .filter(|kind| **kind == EnemyKind::Diver)
+        .count();
+    assert_eq!(divers, 2);
+}
+
+#[test]
+fn a_shot_that_kills_a_minion_misses_the_boss() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.step();
+    let boss = spawn_boss(&mut game, Boss::new());
+    game.step();
+
+    let center = game.world().get::<Transform>(boss).unwrap().translation;
+    game.world().spawn((Transform::from_translation(center), Enemy));
+    shoot_at(&mut game, center.truncate());
+    game.step();
+    assert_eq!(game.score(), Some(1));
+    assert_eq!(boss_health(&mut game, boss), Boss::MAX_HEALTH);
+}
+
+#[test]
+fn beating_the_boss_is_worth_a_lot() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.step();
+    let mut boss = Boss::new();
+    boss.health = 1;
+    let boss = spawn_boss(&mut game, boss);
+    game.step();
+
+    let center = game.world().get::<Transform>(boss).unwrap().translation.truncate();
+    shoot_at(&mut game, center);
+    game.step();
+    assert!(game.world().get_entity(boss).is_none());
+    assert!(game.score().unwrap() >= 10);
+}
+
+#[test]
+fn a_boss_comes_in_at_a_score_milestone() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    // once the first wave is in
+    game.run_frames(125);
+    let bosses = |game: &mut HeadlessGame| game.world().query_filtered::<(), With<Boss>>().iter(game.world()).count();
+    assert_eq!(bosses(&mut game), 0);
+
+    game.world().resource_mut::<Score>().score = 1000;
+    game.run_frames(2);
+    assert_eq!(bosses(&mut game), 1);
+    // only once per milestone
+    let boss = game.world().query_filtered::<Entity, With<Boss>>().single(game.world());
+    game.world().entity_mut(boss).despawn_recursive();
+    game.run_frames(2);
+    assert_eq!(bosses(&mut game), 0);
+}
+
+fn player_projectiles(game: &mut HeadlessGame) -> Vec<Vec2> {
+    game.world()
+        .query_filtered::<&Transform, With<Projectile>>()
+        .iter(game.world())
+        .map(|transform| transform.translation.truncate())
+        .collect()
+}
+
+// drops `pickup` on the player and lets them catch it
+fn catch(game: &mut HeadlessGame, pickup: PickUp) {
+    let position = player_position(game);
+    game.world().spawn((Transform::from_translation(position), pickup));
+    game.step();
+}
+
+#[test]
+fn killed_enemies_drop_power_ups() {
+    let mut game = HeadlessGame::with_seed(CrabShooter, 3);
+    game.step();
+    for i in 0..40 {
+        let position = Vec3::new(-400.0 + 20.0 * i as f32, 200.0, 1.0);
+        game.world().spawn((Transform::from_translation(position), Enemy));
+        game.world().spawn((Transform::from_translation(position), Projectile));
+    }
+    game.step();
+    assert_eq!(game.score(), Some(40));
+    let pickups = game.world().query::<&PickUp>().iter(game.world()).count();
+    assert!(pickups > 0);
+}
+
+#[test]
+fn shot_upgrades_fire_more_projectiles() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.step();
+    assert_eq!(game.world().resource::<Weapon>().level(), 1);
+
+    catch(&mut game, PickUp::DoubleShot);
+    assert_eq!(game.world().resource::<Weapon>().shot, Shot::Double);
+    game.hold(KeyCode::Space, 31);
+    let shots = player_projectiles(&mut game);
+    assert_eq!(shots.len(), 2);
+    assert_ne!(shots[0].x, shots[1].x);
+
+    catch(&mut game, PickUp::TripleShot);
+    assert_eq!(game.world().resource::<Weapon>().level(), 3);
+    game.hold(KeyCode::Space, 31);
+    // the two double shots and the new fan of three
+    let sideways: Vec<f32> = game
+        .world()
+        .query_filtered::<&ProjectileVelocity, With<Projectile>>()
+        .iter(game.world())
+        .map(|velocity| velocity.0.x)
+        .collect();
+    assert_eq!(sideways.len(), 5);
+    assert!(sideways.iter().any(|&x| x < 0.0) && sideways.iter().any(|&x| x > 0.0));
+}
+
+#[test]
+fn rapid_fire_shoots_twice_as_often() {
+    let shots = |rapid_fire: bool| {
+        let mut game = HeadlessGame::new(CrabShooter);
+        game.step();
+        if rapid_fire {
+            catch(&mut game, PickUp::RapidFire);
+        }
+        game.hold(KeyCode::Space, 70);
+        player_projectiles(&mut game).len()
+    };
+    assert_eq!(shots(false), 2);
+    assert_eq!(shots(true), 4);
+}
+
+#[test]
+fn piercing_shots_go_through_enemies() {
+    let hits = |piercing: bool| {
+        let mut game = HeadlessGame::new(CrabShooter);
+        game.step();
+        let mut projectile = game.world().spawn((Transform::from_xyz(0.0, 0.0, 1.0), Projectile));
+        if piercing {
+            projectile.insert(Piercing);
+        }
+        game.world().spawn((Transform::from_xyz(0.0, 10.0, 1.0), Enemy));
+        game.world().spawn((Transform::from_xyz(0.0, 100.0, 1.0), Enemy));
+        game.run_frames(30);
+        game.score().unwrap()
+    };
+    assert_eq!(hits(false), 1);
+    assert_eq!(hits(true), 2);
+}
+
+fn charged_shots(game: &mut HeadlessGame) -> usize {
+    game.world().query::<&Charged>().iter(game.world()).count()
+}
+
+#[test]
+fn holding_fire_charges_a_big_shot() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.step();
+    catch(&mut game, PickUp::ChargeShot);
+    game.run_frames(30);
+
+    // a quick tap fires a normal shot
+    game.hold(KeyCode::Space, 10);
+    game.step();
+    assert_eq!(player_projectiles(&mut game).len(), 1);
+    assert_eq!(charged_shots(&mut game), 0);
+
+    // holding only charges
+    game.press(KeyCode::Space);
+    game.run_frames(70);
+    assert_eq!(player_projectiles(&mut game).len(), 1);
+    game.release(KeyCode::Space);
+    game.step();
+    assert_eq!(charged_shots(&mut game), 1);
+}
+
+#[test]
+fn charged_shots_go_through_enemy_projectiles() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.step();
+    let boss = spawn_boss(&mut game, Boss::new());
+    game.step();
+
+    let below_boss = game.world().get::<Transform>(boss).unwrap().translation - Vec3::new(0.0, 200.0, 0.0);
+    game.world().spawn((Transform::from_translation(below_boss), EnemyProjectile));
+    game.world().spawn((
+        Transform::from_translation(below_boss).with_scale(Vec3::splat(2.5)),
+        Projectile,
+        Charged,
+        Piercing,
+    ));
+    game.step();
+    assert_eq!(game.world().query::<&EnemyProjectile>().iter(game.world()).count(), 0);
+    assert_eq!(charged_shots(&mut game), 1);
+
+    game.run_frames(60);
+    assert_eq!(charged_shots(&mut game), 0);
+    assert_eq!(boss_health(&mut game, boss), Boss::MAX_HEALTH - CHARGED_DAMAGE);
+}
+
+#[test]
+fn the_shield_blocks_hits_until_it_wears_off() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.step();
+    catch(&mut game, PickUp::Shield);
+
+    let position = player_position(&mut game);
+    game.world().spawn((Transform::from_translation(position), EnemyProjectile));
+    game.run_frames(2);
+    assert_eq!(game.health(), Some(3));
+
+    game.run_frames(8 * 60);
+    let player = game.world().query_filtered::<Entity, With<Player>>().single(game.world());
+    assert!(game.world().get::<PlayerShield>(player).is_none());
+
+    // hits hurt again once it's gone
+    let position = player_position(&mut game);
+    game.world().spawn((Transform::from_translation(position), EnemyProjectile));
+    game.run_frames(2);
+    assert_eq!(game.health(), Some(2));
+}
+
+#[test]
+fn a_bomb_clears_enemy_projectiles() {
+    let mut game = HeadlessGame::new(CrabShooter);
+    game.step();
+    for x in [-300.0, 0.0, 300.0] {
+        game.world().spawn((Transform::from_xyz(x, 300.0, 1.0), EnemyProjectile));
+    }
+    catch(&mut game, PickUp::Bomb);
+    game.step();
+    assert_eq!(game.world().query::<&EnemyProjectile>().iter(game.world()).count(), 0);
+}
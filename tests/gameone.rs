@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+use crusty_crab::controls::{Action, KeyBindings};
+use crusty_crab::gameone::{CatchTheCrab, Crab, Paddle, Velocity};
+use crusty_crab::headless::HeadlessGame;
+
+fn paddle_position(game: &mut HeadlessGame) -> Vec3 {
+    game.world()
+        .query_filtered::<&Transform, With<Paddle>>()
+        .single(game.world())
+        .translation
+}
+
+#[test]
+fn crab_hitting_the_paddle_scores_a_point() {
+    let mut game = HeadlessGame::new(CatchTheCrab);
+    game.step();
+    assert_eq!(game.score(), Some(0));
+
+    // drop the crab straight onto the middle of the paddle
+    let paddle = paddle_position(&mut game);
+    let (mut transform, mut velocity) = game
+        .world()
+        .query_filtered::<(&mut Transform, &mut Velocity), With<Crab>>()
+        .single_mut(game.world());
+    transform.translation = Vec3::new(paddle.x, paddle.y + 27.0, 1.0);
+    velocity.0 = Vec2::new(0.0, -400.0);
+
+    game.run_frames(5);
+    assert_eq!(game.score(), Some(1));
+    let velocity = game
+        .world()
+        .query_filtered::<&Velocity, With<Crab>>()
+        .single(game.world());
+    assert!(velocity.y > 0.0, "the crab should bounce back up");
+}
+
+#[test]
+fn arrow_keys_move_the_paddle() {
+    let mut game = HeadlessGame::new(CatchTheCrab);
+    game.step();
+    let start = paddle_position(&mut game);
+
+    game.hold(KeyCode::ArrowRight, 20);
+    let moved_right = paddle_position(&mut game);
+    assert!(moved_right.x > start.x);
+
+    game.hold(KeyCode::ArrowLeft, 40);
+    assert!(paddle_position(&mut game).x < moved_right.x);
+}
+
+#[test]
+fn rebound_keys_move_the_paddle() {
+    let mut game = HeadlessGame::new(CatchTheCrab);
+    game.world()
+        .resource_mut::<KeyBindings>()
+        .rebind("gameone", Action::MoveRight, KeyCode::KeyL);
+    game.step();
+    let start = paddle_position(&mut game);
+
+    game.hold(KeyCode::ArrowRight, 20);
+    assert_eq!(paddle_position(&mut game).x, start.x, "the old key no longer moves the paddle");
+
+    game.hold(KeyCode::KeyL, 20);
+    assert!(paddle_position(&mut game).x > start.x);
+}
@@ -0,0 +1,262 @@
+use bevy::prelude::*;
+use bevy::window::WindowResized;
+use crusty_crab::gametwo::{
+    ActivePowerUps, Combo, FallingItem, ItemCaught, ItemDef, ItemEffect, PowerUp, SeafoodScramble,
+};
+use crusty_crab::headless::HeadlessGame;
+use crusty_crab::difficulty::Difficulty;
+use crusty_crab::hud::{Health, Level};
+use crusty_crab::player::Player;
+use crusty_crab::playfield::PLAYFIELD;
+use crusty_crab::GameState;
+
+fn player_position(game: &mut HeadlessGame) -> Vec3 {
+    game.world()
+        .query_filtered::<&Transform, With<Player>>()
+        .single(game.world())
+        .translation
+}
+
+#[test]
+fn resizing_the_window_leaves_the_playfield_alone() {
+    let mut game = HeadlessGame::new(SeafoodScramble);
+    game.step();
+    let before = player_position(&mut game);
+    assert!(before.y < PLAYFIELD.bottom() + PLAYFIELD.height / 4.0);
+
+    game.world().send_event(WindowResized {
+        window: Entity::PLACEHOLDER,
+        width: 800.0,
+        height: 400.0,
+    });
+    game.run_frames(2);
+    assert_eq!(player_position(&mut game), before);
+}
+
+#[test]
+fn restarting_resets_health_and_score() {
+    let mut game = HeadlessGame::new(SeafoodScramble);
+    game.step();
+    game.world().resource_mut::<Health>().health = 1;
+
+    game.world()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Restarting("gametwo"));
+    game.run_frames(3);
+    assert_eq!(game.health(), Some(3));
+    assert_eq!(game.score(), Some(0));
+}
+
+#[test]
+fn catching_items_scores_hurts_and_sends_item_caught() {
+    let mut game = HeadlessGame::new(SeafoodScramble);
+    game.step();
+    let position = player_position(&mut game);
+    let size = Vec2::splat(50.0);
+    game.world().spawn((
+        Transform::from_translation(position),
+        FallingItem(ItemDef { name: "shrimp".into(), size, score: 1, ..default() }),
+    ));
+    game.world().spawn((
+        Transform::from_translation(position),
+        FallingItem(ItemDef { name: "tire".into(), size, damage: 1, ..default() }),
+    ));
+    game.step();
+
+    assert_eq!(game.score(), Some(1));
+    assert_eq!(game.health(), Some(2));
+    let events = game.world().resource::<Events<ItemCaught>>();
+    let mut caught: Vec<_> =
+        events.get_reader().read(events).map(|event| (event.kind.clone(), event.good)).collect();
+    caught.sort_by_key(|(_, good)| *good);
+    assert_eq!(caught, [("tire".to_string(), false), ("shrimp".to_string(), true)]);
+}
+
+#[test]
+fn healing_items_restore_health_up_to_the_start() {
+    let mut game = HeadlessGame::new(SeafoodScramble);
+    game.step();
+    game.world().resource_mut::<Health>().health = 2;
+    let position = player_position(&mut game);
+    for _ in 0..2 {
+        game.world().spawn((
+            Transform::from_translation(position),
+            FallingItem(ItemDef {
+                name: "kelp".into(),
+                size: Vec2::splat(50.0),
+                effect: Some(ItemEffect::Heal(1)),
+                ..default()
+            }),
+        ));
+    }
+    game.step();
+    assert_eq!(game.health(), Some(3));
+}
+
+#[test]
+fn items_come_from_the_item_file() {
+    let mut game = HeadlessGame::new(SeafoodScramble);
+    // the first item drops after half a second
+    game.run_frames(40);
+    let names: Vec<String> = game
+        .world()
+        .query::<&FallingItem>()
+        .iter(game.world())
+        .map(|item| item.name.clone())
+        .collect();
+    assert!(!names.is_empty());
+    let known = [
+        "shrimp", "fish", "algae", "worm", "sea lettuce", "rock", "fishing hook", "tire", "heart", "shell", "magnet",
+        "hourglass", "star",
+    ];
+    assert!(names.iter().all(|name| known.contains(&name.as_str())), "{names:?}");
+}
+
+fn items_dropped_in_a_second(difficulty: Difficulty) -> usize {
+    let mut game = HeadlessGame::new(SeafoodScramble);
+    game.world().insert_resource(difficulty);
+    game.run_frames(60);
+    game.world().query::<&FallingItem>().iter(game.world()).count()
+}
+
+#[test]
+fn harder_presets_drop_items_more_often() {
+    assert!(items_dropped_in_a_second(Difficulty::Easy) < items_dropped_in_a_second(Difficulty::Hard));
+}
+
+#[test]
+fn the_level_goes_up_as_the_run_goes_on() {
+    let mut game = HeadlessGame::new(SeafoodScramble);
+    game.step();
+    game.world().resource_mut::<Health>().health = 1000;
+    assert_eq!(game.world().resource::<Level>().level, 1);
+    // 20 seconds per level
+    game.run_frames(20 * 60 + 1);
+    assert_eq!(game.world().resource::<Level>().level, 2);
+}
+
+fn drop_on_player(game: &mut HeadlessGame, item: ItemDef) {
+    let position = player_position(game);
+    game.world().spawn((Transform::from_translation(position), FallingItem(item)));
+    game.step();
+}
+
+fn shrimp() -> ItemDef {
+    ItemDef { name: "shrimp".into(), size: Vec2::splat(50.0), score: 1, ..default() }
+}
+
+#[test]
+fn food_in_a_row_raises_the_multiplier_and_junk_resets_it() {
+    let mut game = HeadlessGame::new(SeafoodScramble);
+    game.step();
+    game.world().insert_resource(Health { health: 1000 });
+    for _ in 0..4 {
+        drop_on_player(&mut game, shrimp());
+    }
+    // three at x1, then x2
+    assert_eq!(game.score(), Some(5));
+    assert_eq!(game.world().resource::<Combo>().multiplier(), 2);
+
+    drop_on_player(&mut game, ItemDef { name: "tire".into(), size: Vec2::splat(50.0), damage: 1, ..default() });
+    assert_eq!(game.world().resource::<Combo>().multiplier(), 1);
+}
+
+#[test]
+fn missing_food_breaks_the_streak() {
+    let mut game = HeadlessGame::new(SeafoodScramble);
+    game.step();
+    for _ in 0..3 {
+        drop_on_player(&mut game, shrimp());
+    }
+    assert_eq!(game.world().resource::<Combo>().streak, 3);
+
+    // already below the bottom of the screen
+    game.world().spawn((Transform::from_xyz(0.0, -1000.0, 0.0), FallingItem(shrimp())));
+    game.step();
+    assert_eq!(game.world().resource::<Combo>().streak, 0);
+}
+
+#[test]
+fn catching_food_shows_the_points_gained() {
+    let mut game = HeadlessGame::new(SeafoodScramble);
+    game.step();
+    drop_on_player(&mut game, shrimp());
+    game.step();
+    let popups: Vec<String> = game
+        .world()
+        .query::<&Text>()
+        .iter(game.world())
+        .map(|text| text.sections[0].value.clone())
+        .filter(|text| text.starts_with('+'))
+        .collect();
+    assert_eq!(popups, ["+1"]);
+}
+
+fn power_up(power_up: PowerUp, seconds: f32) -> ItemDef {
+    ItemDef {
+        name: "power-up".into(),
+        size: Vec2::splat(50.0),
+        effect: Some(ItemEffect::PowerUp(power_up, seconds)),
+        ..default()
+    }
+}
+
+fn tire() -> ItemDef {
+    ItemDef { name: "tire".into(), size: Vec2::splat(50.0), damage: 1, ..default() }
+}
+
+#[test]
+fn a_shield_absorbs_one_hit() {
+    let mut game = HeadlessGame::new(SeafoodScramble);
+    game.step();
+    drop_on_player(&mut game, power_up(PowerUp::Shield, 10.0));
+    assert!(game.world().resource::<ActivePowerUps>().is_active(PowerUp::Shield));
+
+    drop_on_player(&mut game, tire());
+    assert_eq!(game.health(), Some(3));
+    assert!(!game.world().resource::<ActivePowerUps>().is_active(PowerUp::Shield));
+    drop_on_player(&mut game, tire());
+    assert_eq!(game.health(), Some(2));
+}
+
+#[test]
+fn double_points_runs_out() {
+    let mut game = HeadlessGame::new(SeafoodScramble);
+    game.step();
+    drop_on_player(&mut game, power_up(PowerUp::DoublePoints, 1.0));
+    drop_on_player(&mut game, shrimp());
+    assert_eq!(game.score(), Some(2));
+
+    game.run_frames(60);
+    assert_eq!(game.world().resource::<ActivePowerUps>().remaining(PowerUp::DoublePoints), None);
+    drop_on_player(&mut game, shrimp());
+    assert_eq!(game.score(), Some(3));
+}
+
+fn item_height_after(power_ups: &[PowerUp], frames: usize) -> Vec3 {
+    let mut game = HeadlessGame::new(SeafoodScramble);
+    game.step();
+    for &effect in power_ups {
+        drop_on_player(&mut game, power_up(effect, 10.0));
+    }
+    let start = player_position(&mut game) + Vec3::new(200.0, 300.0, 0.0);
+    let item = game
+        .world()
+        .spawn((Transform::from_translation(start), FallingItem(ItemDef { fall_speed: 100.0, ..shrimp() })))
+        .id();
+    game.run_frames(frames);
+    game.world().get::<Transform>(item).unwrap().translation - start
+}
+
+#[test]
+fn slow_motion_halves_the_fall_speed() {
+    let normal = item_height_after(&[], 30);
+    let slow = item_height_after(&[PowerUp::SlowMotion], 30);
+    assert!((slow.y - normal.y / 2.0).abs() < 0.01, "{slow} {normal}");
+}
+
+#[test]
+fn the_magnet_pulls_food_towards_the_player() {
+    assert_eq!(item_height_after(&[], 30).x, 0.0);
+    assert!(item_height_after(&[PowerUp::Magnet], 30).x < 0.0);
+}
@@ -0,0 +1,17 @@
+use bevy::prelude::*;
+use crusty_crab::playfield::PLAYFIELD;
+
+#[test]
+fn the_playfield_is_centered_on_the_origin() {
+    assert_eq!(PLAYFIELD.left(), -PLAYFIELD.right());
+    assert_eq!(PLAYFIELD.bottom(), -PLAYFIELD.top());
+    assert_eq!(PLAYFIELD.right() - PLAYFIELD.left(), PLAYFIELD.width);
+}
+
+#[test]
+fn positions_past_the_margin_are_outside() {
+    assert!(!PLAYFIELD.is_outside(Vec2::ZERO, 0.0));
+    assert!(!PLAYFIELD.is_outside(Vec2::new(PLAYFIELD.right() + 10.0, 0.0), 20.0));
+    assert!(PLAYFIELD.is_outside(Vec2::new(PLAYFIELD.right() + 30.0, 0.0), 20.0));
+    assert!(PLAYFIELD.is_outside(Vec2::new(0.0, PLAYFIELD.bottom() - 1.0), 0.0));
+}
@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+use crusty_crab::difficulty::Difficulty;
+use crusty_crab::gametwo::SeafoodScramble;
+use crusty_crab::headless::HeadlessGame;
+use crusty_crab::replay::Replay;
+
+fn record_run(difficulty: Difficulty) -> (HeadlessGame, Replay) {
+    let mut game = HeadlessGame::with_seed(SeafoodScramble, 7);
+    game.world().insert_resource(difficulty);
+    // walk back and forth until something hits hard enough
+    for step in 0..200 {
+        if game.is_game_over() {
+            break;
+        }
+        let key = if step % 2 == 0 { KeyCode::KeyA } else { KeyCode::KeyD };
+        game.hold(key, 45);
+        game.run_frames(step % 7);
+    }
+    assert!(game.is_game_over());
+    let recorded = game.last_replay().expect("the run was recorded").clone();
+    (game, recorded)
+}
+
+// Recorded with `record_run(Difficulty::Normal)` and saved with the game as it was then. If a
+// gameplay change breaks it on purpose, record it again.
+const RECORDED_REPLAY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/replays/gametwo_seed7.ron");
+
+#[test]
+fn a_replayed_run_reaches_the_recorded_score() {
+    let recorded = Replay::load(RECORDED_REPLAY).expect("the replay file loads");
+    assert_eq!(recorded.game, "gametwo");
+    assert!(recorded.final_score > 0);
+
+    let replayed = HeadlessGame::replay(SeafoodScramble, recorded.clone());
+    assert!(replayed.is_game_over());
+    assert_eq!(replayed.score(), Some(recorded.final_score));
+}
+
+#[test]
+fn a_replay_keeps_the_difficulty_it_was_recorded_at() {
+    let (game, recorded) = record_run(Difficulty::Hard);
+    assert_eq!(recorded.difficulty, Difficulty::Hard);
+
+    // watched with the launcher still set to Normal
+    let replayed = HeadlessGame::replay(SeafoodScramble, recorded);
+    assert!(replayed.is_game_over());
+    assert_eq!(replayed.score(), game.score());
+}
@@ -0,0 +1,23 @@
+use crusty_crab::difficulty::Difficulty;
+use crusty_crab::settings::{DisplayMode, Resolution, Settings, Vsync};
+
+#[test]
+fn settings_missing_from_the_file_keep_their_defaults() {
+    let settings: Settings = ron::from_str("(display_mode: Fullscreen, difficulty: Hard)").unwrap();
+    assert_eq!(settings.display_mode, DisplayMode::Fullscreen);
+    assert_eq!(settings.difficulty, Difficulty::Hard);
+    assert_eq!(settings.vsync, Vsync::On);
+    assert_eq!(settings.resolution, Resolution(1280, 720));
+}
+
+#[test]
+fn settings_survive_a_round_trip() {
+    let settings = Settings {
+        display_mode: DisplayMode::Borderless,
+        vsync: Vsync::Off,
+        resolution: Resolution(1920, 1080),
+        difficulty: Difficulty::Easy,
+    };
+    let text = ron::to_string(&settings).unwrap();
+    assert_eq!(ron::from_str::<Settings>(&text).unwrap(), settings);
+}